@@ -0,0 +1,249 @@
+use std::str::FromStr;
+use wincounter::util::Util;
+
+pub const USAGE: &str = "\
+Tallies game outcomes and reports who won.
+
+Usage: wincounter [report] [OPTIONS] [FILE]...
+       wincounter merge --output <OUT> [OPTIONS] <FILE>...
+
+Reads every FILE, or stdin if there aren't any. A FILE of `-` is stdin too.
+
+`merge` adds saved tallies together, after checking that they agree on the number of players,
+and with --players and --labels if they're given, writes the total to OUT, and then reports on
+it. OUT is saved as JSON or CSV if it ends in `.json` or `.csv`, and in the wincounter binary
+format otherwise.
+
+Options:
+  -i, --input <KIND>     How the outcomes are written [default: flags]
+                           flags    one or more flag integers per line (`3`, `0b11`, `0x3`)
+                           indexes  one outcome per line, as comma separated player indexes
+                                    counting from zero (`0,1` is a tie between the first two),
+                                    or player labels if there are any
+                           tally    a saved tally: CSV, JSON or wincounter binary
+                         For flags and indexes, `none` is an outcome nobody won.
+  -f, --format <FORMAT>  text, json, csv or markdown [default: text]
+  -p, --players <N>      Number of players [default: the highest player seen]
+  -l, --labels <NAMES>   Comma separated names for the players, in order [default: the saved
+                         labels, if there are any]
+  -o, --output <OUT>     Save the combined tally to OUT as well [required for `merge`]
+      --heads-up         Report the first two players as a heads up contest
+      --head-to-head     Report every pair of players against each other
+  -h, --help             Print this message
+";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Input {
+    #[default]
+    Flags,
+    Indexes,
+    Tally,
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flags" => Ok(Input::Flags),
+            "indexes" => Ok(Input::Indexes),
+            "tally" => Ok(Input::Tally),
+            _ => Err(format!("unknown input `{s}`")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!("unknown format `{s}`")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Command {
+    #[default]
+    Report,
+    Merge,
+    Help,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub input: Input,
+    pub format: Format,
+    pub players: Option<usize>,
+    pub labels: Vec<String>,
+    pub heads_up: bool,
+    pub head_to_head: bool,
+    pub output: Option<String>,
+    pub paths: Vec<String>,
+}
+
+impl Args {
+    /// Everything after the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().peekable();
+        match args.peek().map(String::as_str) {
+            Some("report") => {
+                args.next();
+            },
+            Some("merge") => {
+                args.next();
+                parsed.command = Command::Merge;
+                parsed.input = Input::Tally;
+            },
+            _ => {},
+        }
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| -> Result<String, String> {
+                inline
+                    .map(ToString::to_string)
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{name} needs a value"))
+            };
+            match flag.as_str() {
+                "-h" | "--help" => parsed.command = Command::Help,
+                "-i" | "--input" => parsed.input = value(&flag)?.parse()?,
+                "-f" | "--format" => parsed.format = value(&flag)?.parse()?,
+                "-p" | "--players" => {
+                    let n = value(&flag)?;
+                    parsed.players =
+                        Some(n.parse().map_err(|_| format!("bad player count `{n}`"))?);
+                },
+                "-l" | "--labels" => {
+                    parsed.labels = Util::str_splitter(&value(&flag)?, ",")
+                        .iter()
+                        .map(|label| label.trim().to_string())
+                        .collect();
+                },
+                "-o" | "--output" => parsed.output = Some(value(&flag)?),
+                "--heads-up" => parsed.heads_up = true,
+                "--head-to-head" => parsed.head_to_head = true,
+                "-" => parsed.paths.push(arg),
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+                _ => parsed.paths.push(arg),
+            }
+        }
+        if parsed.heads_up && parsed.head_to_head {
+            return Err("pick one of --heads-up and --head-to-head".to_string());
+        }
+        if parsed.command == Command::Merge {
+            if parsed.output.is_none() {
+                return Err("merge needs an --output".to_string());
+            }
+            if parsed.paths.is_empty() {
+                return Err("merge needs at least one tally to read".to_string());
+            }
+            if parsed.input != Input::Tally {
+                return Err("merge only reads saved tallies".to_string());
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod util__wincounter__args__tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn parse__defaults() {
+        assert_eq!(Args::default(), parse(&[]).unwrap());
+        assert_eq!(Args::default(), parse(&["report"]).unwrap());
+    }
+
+    #[test]
+    fn parse__options() {
+        let args = parse(&[
+            "-i",
+            "indexes",
+            "--format=markdown",
+            "--players",
+            "3",
+            "--heads-up",
+            "--labels",
+            "Negreanu, Hansen",
+            "a.txt",
+            "-",
+        ])
+        .unwrap();
+
+        assert_eq!(Input::Indexes, args.input);
+        assert_eq!(Format::Markdown, args.format);
+        assert_eq!(Some(3), args.players);
+        assert!(args.heads_up);
+        assert_eq!(vec!["Negreanu", "Hansen"], args.labels);
+        assert_eq!(vec!["a.txt", "-"], args.paths);
+    }
+
+    #[test]
+    fn parse__merge() {
+        let args = parse(&["merge", "-o", "total.wcnt", "a.wcnt", "b.csv"]).unwrap();
+
+        assert_eq!(Command::Merge, args.command);
+        assert_eq!(Input::Tally, args.input);
+        assert_eq!(Some("total.wcnt".to_string()), args.output);
+        assert_eq!(vec!["a.wcnt", "b.csv"], args.paths);
+    }
+
+    #[test]
+    fn parse__merge__errors() {
+        assert_eq!(
+            "merge needs an --output",
+            parse(&["merge", "a.wcnt"]).unwrap_err()
+        );
+        assert_eq!(
+            "merge needs at least one tally to read",
+            parse(&["merge", "-o", "total.wcnt"]).unwrap_err()
+        );
+        assert_eq!(
+            "merge only reads saved tallies",
+            parse(&["merge", "-o", "t.wcnt", "-i", "flags", "a"]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn parse__help() {
+        assert_eq!(Command::Help, parse(&["--help"]).unwrap().command);
+    }
+
+    #[test]
+    fn parse__errors() {
+        assert_eq!("unknown option `-x`", parse(&["-x"]).unwrap_err());
+        assert_eq!("--format needs a value", parse(&["--format"]).unwrap_err());
+        assert_eq!("unknown format `xml`", parse(&["-f", "xml"]).unwrap_err());
+        assert_eq!("bad player count `two`", parse(&["-p", "two"]).unwrap_err());
+        assert_eq!(
+            "pick one of --heads-up and --head-to-head",
+            parse(&["--heads-up", "--head-to-head"]).unwrap_err()
+        );
+    }
+}
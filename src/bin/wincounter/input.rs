@@ -0,0 +1,304 @@
+use crate::args::Input;
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use wincounter::binary::{self, Reader};
+use wincounter::player::{Player, PlayerSet};
+use wincounter::tally::{self, Tally};
+use wincounter::util::Util;
+use wincounter::wins::Wins;
+
+/// The CLI always counts with the widest flag, so it never has to ask how many players there
+/// are before it starts.
+pub type CliFlag = u128;
+
+/// Written in place of a flag, or a line of indexes, for an outcome nobody won.
+pub const NO_WINNER: &str = "none";
+
+/// Reads every path, or stdin if there aren't any, and merges them into one `Tally`. `labels`
+/// are only used to look up players by name in `indexes` input.
+pub fn read_all(
+    input: Input,
+    paths: &[String],
+    labels: &[String],
+) -> Result<Tally<CliFlag>, String> {
+    let stdin = ["-".to_string()];
+    let paths = if paths.is_empty() { &stdin[..] } else { paths };
+    let tallies = paths
+        .iter()
+        .map(|path| read(input, path, labels).map_err(|e| format!("{path}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    tally::merge(tallies).map_err(|e| e.to_string())
+}
+
+/// Puts `--players` and `--labels` on the tally. When `merging`, they can only fill in what the
+/// tallies don't already agree on, since checking that they agree is the point of `merge`.
+pub fn apply_flags(
+    tally: &mut Tally<CliFlag>,
+    players: Option<usize>,
+    labels: &[String],
+    merging: bool,
+) -> Result<(), String> {
+    if let Some(players) = players {
+        match tally.player_count {
+            Some(merged) if merging && merged != players => {
+                return Err(format!(
+                    "--players {players} doesn't match the {merged} players in the tallies"
+                ));
+            },
+            _ => {},
+        }
+        let highest = tally.highest_player();
+        if highest > players {
+            return Err(format!(
+                "--players {players} is too few, the tallies have player #{highest}"
+            ));
+        }
+        tally.player_count = Some(players);
+    }
+    if !labels.is_empty() {
+        if merging && !tally.labels.is_empty() && tally.labels != labels {
+            return Err(format!(
+                "--labels {} doesn't match the tallies' {}",
+                labels.join(","),
+                tally.labels.join(",")
+            ));
+        }
+        tally.labels = labels.to_vec();
+    }
+    Ok(())
+}
+
+fn read(input: Input, path: &str, labels: &[String]) -> Result<Tally<CliFlag>, String> {
+    let mut wins = Wins::new();
+    match input {
+        Input::Flags => for_each_line(path, |line| {
+            for token in line.split_whitespace() {
+                if token == NO_WINNER {
+                    wins.add_no_winner();
+                } else {
+                    wins.add(parse_flag(token)?);
+                }
+            }
+            Ok(())
+        })?,
+        Input::Indexes => for_each_line(path, |line| {
+            if line == NO_WINNER {
+                wins.add_no_winner();
+            } else {
+                wins.add(parse_indexes(line, labels)?);
+            }
+            Ok(())
+        })?,
+        Input::Tally => return read_tally(&read_bytes(path)?),
+    }
+    Ok(Tally::from(wins))
+}
+
+/// Hands every line that isn't blank or a `#` comment to `f`, with the line number on any error.
+fn for_each_line<F>(path: &str, mut f: F) -> Result<(), String>
+where
+    F: FnMut(&str) -> Result<(), String>,
+{
+    let lines: Box<dyn Iterator<Item = io::Result<String>>> = if path == "-" {
+        Box::new(io::stdin().lock().lines())
+    } else {
+        Box::new(Util::read_lines(path).map_err(|e| e.to_string())?)
+    };
+    for (i, line) in lines.enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        f(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+    }
+    Ok(())
+}
+
+fn read_bytes(path: &str) -> Result<Vec<u8>, String> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .map_err(|e| e.to_string())?;
+        Ok(bytes)
+    } else {
+        std::fs::read(path).map_err(|e| e.to_string())
+    }
+}
+
+/// Works out which kind of saved tally it is from the first few bytes. CSV tallies don't know
+/// how many players there were, or what they were called.
+pub fn read_tally(bytes: &[u8]) -> Result<Tally<CliFlag>, String> {
+    if bytes.starts_with(&binary::MAGIC) {
+        let reader: Reader<_, CliFlag> = Reader::new(bytes).map_err(|e| e.to_string())?;
+        return Tally::try_from(reader).map_err(|e| e.to_string());
+    }
+    let text = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
+    if text.trim_start().starts_with('{') {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    } else {
+        Ok(Tally::from(
+            Wins::from_csv(text).map_err(|e| e.to_string())?,
+        ))
+    }
+}
+
+/// Saves as JSON or CSV if the path ends in `.json` or `.csv`, and as binary otherwise.
+pub fn write_tally(tally: &Tally<CliFlag>, path: &str) -> Result<(), String> {
+    let result = match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::to_string_pretty(tally)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(path, json + "\n").map_err(|e| e.to_string())),
+        Some("csv") => tally.wins.write_csv(path).map_err(|e| e.to_string()),
+        _ => tally.write_binary(path).map_err(|e| e.to_string()),
+    };
+    result.map_err(|e| format!("{path}: {e}"))
+}
+
+/// Decimal, or binary and hex with a `0b` or `0x` in front.
+pub fn parse_flag(token: &str) -> Result<CliFlag, String> {
+    let parsed = if let Some(bits) = token.strip_prefix("0b") {
+        CliFlag::from_str_radix(bits, 2)
+    } else if let Some(hex) = token.strip_prefix("0x") {
+        CliFlag::from_str_radix(hex, 16)
+    } else {
+        token.parse()
+    };
+    parsed.map_err(|_| format!("bad flag `{token}`"))
+}
+
+/// Anything that isn't a number is looked up in `labels`.
+pub fn parse_indexes(line: &str, labels: &[String]) -> Result<CliFlag, String> {
+    let mut players = PlayerSet::new();
+    for index in Util::str_splitter(line, ",") {
+        let index = index.trim();
+        let player = index
+            .parse()
+            .ok()
+            .or_else(|| labels.iter().position(|label| label == index))
+            .and_then(|i| Player::new(i).ok())
+            .ok_or_else(|| format!("bad player index `{index}`"))?;
+        players.insert(player);
+    }
+    Ok(players.flag())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod util__wincounter__input__tests {
+    use super::*;
+    use wincounter::binary::{Layout, Writer};
+
+    #[test]
+    fn parse_flag() {
+        assert_eq!(3, super::parse_flag("3").unwrap());
+        assert_eq!(3, super::parse_flag("0b11").unwrap());
+        assert_eq!(255, super::parse_flag("0xff").unwrap());
+        assert_eq!("bad flag `x`", super::parse_flag("x").unwrap_err());
+    }
+
+    #[test]
+    fn parse_indexes() {
+        let labels = vec!["Negreanu".to_string(), "Hansen".to_string()];
+
+        assert_eq!(0b101, super::parse_indexes("0, 2", &[]).unwrap());
+        assert_eq!(1 << 127, super::parse_indexes("127", &[]).unwrap());
+        assert_eq!(0b11, super::parse_indexes("Hansen,0", &labels).unwrap());
+        assert_eq!(
+            "bad player index `128`",
+            super::parse_indexes("0,128", &[]).unwrap_err()
+        );
+        assert_eq!(
+            "bad player index `Ivey`",
+            super::parse_indexes("Ivey", &labels).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn apply_flags() {
+        let labels = vec!["a".to_string(), "b".to_string()];
+        let mut tally = Tally::from(Wins::from(vec![0b1, 0b11]));
+
+        super::apply_flags(&mut tally, Some(3), &labels, false).unwrap();
+
+        assert_eq!(Some(3), tally.player_count);
+        assert_eq!(labels, tally.labels);
+    }
+
+    #[test]
+    fn apply_flags__too_few_players() {
+        let mut tally = Tally::from(Wins::from(vec![0b1, 0b101]));
+
+        assert_eq!(
+            "--players 2 is too few, the tallies have player #3",
+            super::apply_flags(&mut tally, Some(2), &[], false).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn apply_flags__merge_mismatch() {
+        let merged = Tally::new(Wins::from(vec![0b1, 0b11]), 3).with_labels(["a", "b", "c"]);
+        let labels = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+
+        assert_eq!(
+            "--players 4 doesn't match the 3 players in the tallies",
+            super::apply_flags(&mut merged.clone(), Some(4), &[], true).unwrap_err()
+        );
+        assert_eq!(
+            "--labels x,y,z doesn't match the tallies' a,b,c",
+            super::apply_flags(&mut merged.clone(), None, &labels, true).unwrap_err()
+        );
+        // Reporting on a saved tally can still rename its players.
+        let mut tally = merged.clone();
+        super::apply_flags(&mut tally, Some(4), &labels, false).unwrap();
+        assert_eq!(Some(4), tally.player_count);
+        assert_eq!(labels, tally.labels);
+        // And merging can fill in what the tallies don't know.
+        let mut tally = Tally::from(merged.wins);
+        super::apply_flags(&mut tally, Some(3), &labels, true).unwrap();
+        assert_eq!(Some(3), tally.player_count);
+    }
+
+    #[test]
+    fn read_tally__csv_and_json() {
+        let csv = read_tally(b"pattern,players,count\n1,1,3\n").unwrap();
+        let json = read_tally(br#"{"version":1,"width":16,"patterns":[[1,2],[3,1]]}"#).unwrap();
+
+        assert_eq!(None, csv.player_count);
+        assert_eq!(3, csv.wins.count(1));
+        assert_eq!(None, json.player_count);
+        assert_eq!(1, json.wins.count(3));
+    }
+
+    #[test]
+    fn read_tally__binary() {
+        let mut writer = Writer::<_, u16>::new(Vec::new(), Layout::Tally, 4).unwrap();
+        writer.add_x(0b11, 7).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let tally = read_tally(&bytes).unwrap();
+
+        assert_eq!(Some(4), tally.player_count);
+        assert_eq!(7, tally.wins.count(0b11));
+    }
+
+    #[test]
+    fn write_tally() {
+        let tally = Tally::new(Wins::from(vec![0b1, 0b11]), 3).with_labels(["a", "b", "c"]);
+        for extension in ["json", "csv", "wcnt"] {
+            let path = std::env::temp_dir().join(format!("wincounter__input__tests.{extension}"));
+            let path = path.to_str().unwrap();
+
+            super::write_tally(&tally, path).unwrap();
+            let back = read_tally(&std::fs::read(path).unwrap()).unwrap();
+
+            assert_eq!(tally.wins, back.wins);
+            if extension != "csv" {
+                assert_eq!(tally, back);
+            }
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
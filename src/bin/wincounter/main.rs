@@ -0,0 +1,86 @@
+//! `wincounter` - tallies streams of game outcomes and reports on them, so I can stop writing
+//! throwaway programs around `Wins` and `WinResults`.
+//!
+//! ```txt
+//! $ printf '1\n2\n3\n1\n' | wincounter
+//! Player #1 75.0% (50.00%/25.00%) [2/1]
+//! Player #2 50.0% (25.00%/25.00%) [1/1]
+//!
+//! $ printf '0\n0,1\n' | wincounter --input indexes --format markdown
+//! | Player | Wins | Ties | Win % | Tie % | Equity % |
+//! |-------:|-----:|-----:|------:|------:|---------:|
+//! | #1 | 1 | 1 | 50.00% | 50.00% | 75.00% |
+//! | #2 | 0 | 1 | 0.00% | 50.00% | 25.00% |
+//! ```
+//!
+//! `wincounter merge --output total.wcnt part-*.wcnt` adds up the partial tallies from a batch
+//! job, saves the total, and reports on it.
+//!
+//! Run `wincounter --help` for everything else.
+#![warn(clippy::pedantic, clippy::unwrap_used, clippy::expect_used)]
+#![cfg_attr(test, allow(clippy::unwrap_used))]
+
+#[cfg(not(target_arch = "wasm32"))]
+mod args;
+#[cfg(not(target_arch = "wasm32"))]
+mod input;
+#[cfg(not(target_arch = "wasm32"))]
+mod report;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    use args::{Args, Command, USAGE};
+    use std::process::ExitCode;
+
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("wincounter: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        },
+    };
+    if args.command == Command::Help {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("wincounter: {e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run(args: &args::Args) -> Result<String, String> {
+    let mut tally = input::read_all(args.input, &args.paths, &args.labels)?;
+    input::apply_flags(
+        &mut tally,
+        args.players,
+        &args.labels,
+        args.command == args::Command::Merge,
+    )?;
+    if let Some(output) = &args.output {
+        input::write_tally(&tally, output)?;
+    }
+    if args.heads_up {
+        return report::heads_up(&tally.wins.results_heads_up(), &tally.labels, args.format);
+    }
+    if args.head_to_head {
+        let player_count = tally.player_count.unwrap_or_else(|| tally.highest_player());
+        let matrix = tally
+            .wins
+            .head_to_head(player_count)
+            .with_labels(tally.labels.iter().cloned());
+        return report::head_to_head(&matrix, args.format);
+    }
+    report::results(&tally.results(), args.format)
+}
+
+/// There's no command line in a browser.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
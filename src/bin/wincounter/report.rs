@@ -0,0 +1,182 @@
+use crate::args::Format;
+use wincounter::head_to_head::HeadToHead;
+use wincounter::heads_up::HeadsUp;
+use wincounter::results::WinResults;
+
+pub fn results(results: &WinResults, format: Format) -> Result<String, String> {
+    match format {
+        Format::Text => Ok(results.to_string()),
+        Format::Json => json(results),
+        Format::Csv => Ok(results.to_csv()),
+        Format::Markdown => Ok(markdown(results)),
+    }
+}
+
+/// `HeadsUp` doesn't have labels of its own, so they're passed in alongside it.
+pub fn heads_up(hup: &HeadsUp, labels: &[String], format: Format) -> Result<String, String> {
+    let results = WinResults::from(hup).with_labels(labels.iter().cloned());
+    match format {
+        Format::Text if labels.is_empty() => Ok(format!("{hup}\n")),
+        Format::Text => Ok(format!(
+            "{}\n",
+            hup.to_string_labeled(&results.label(0), &results.label(1))
+        )),
+        Format::Json => {
+            let mut value = serde_json::to_value(hup).map_err(|e| e.to_string())?;
+            if !labels.is_empty() {
+                value["labels"] = labels.into();
+            }
+            json(&value)
+        },
+        Format::Csv => Ok(results.to_csv()),
+        Format::Markdown => Ok(markdown(&results)),
+    }
+}
+
+pub fn head_to_head(matrix: &HeadToHead, format: Format) -> Result<String, String> {
+    match format {
+        Format::Text => Ok(matrix.to_string()),
+        Format::Json => json(matrix),
+        Format::Csv => Ok(matrix.to_csv()),
+        Format::Markdown => Ok(head_to_head_markdown(matrix)),
+    }
+}
+
+fn json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map(|s| s + "\n")
+        .map_err(|e| e.to_string())
+}
+
+fn markdown(results: &WinResults) -> String {
+    let mut lines = vec![
+        "| Player | Wins | Ties | Win % | Tie % | Equity % |".to_string(),
+        "|-------:|-----:|-----:|------:|------:|---------:|".to_string(),
+    ];
+    for i in 0..results.v.len() {
+        let (wins, ties) = results.wins_and_ties(i);
+        let (win_percentage, tie_percentage) = results.wins_and_ties_percentages(i);
+        lines.push(format!(
+            "| {} | {wins} | {ties} | {win_percentage:.2}% | {tie_percentage:.2}% | {:.2}% |",
+            results.short_label(i).replace('|', "\\|"),
+            results.equity_percentage(i)
+        ));
+    }
+    if results.no_winner > 0 {
+        lines.push(format!(
+            "| No winner | {} | | {:.2}% | | |",
+            results.no_winner,
+            results.no_winner_percentage()
+        ));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Rows beat columns, like the text table.
+fn head_to_head_markdown(matrix: &HeadToHead) -> String {
+    let labels: Vec<String> = (0..matrix.player_count)
+        .map(|i| matrix.short_label(i).replace('|', "\\|"))
+        .collect();
+    let mut lines = vec![
+        format!("| | {} |", labels.join(" | ")),
+        format!("|---|{}", "---:|".repeat(labels.len())),
+    ];
+    for (i, label) in labels.iter().enumerate() {
+        let cells: Vec<String> = (0..matrix.player_count)
+            .map(|j| {
+                matrix.get(i, j).map_or("-".to_string(), |hup| {
+                    format!("{:.2}%", hup.percentage_first())
+                })
+            })
+            .collect();
+        lines.push(format!("| {label} | {} |", cells.join(" | ")));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod util__wincounter__report__tests {
+    use super::*;
+
+    #[test]
+    fn heads_up__markdown() {
+        let hup = HeadsUp::new(1_365_284, 314_904, 32_116);
+
+        assert_eq!(
+            "| Player | Wins | Ties | Win % | Tie % | Equity % |\n\
+             |-------:|-----:|-----:|------:|------:|---------:|\n\
+             | #1 | 1365284 | 32116 | 79.73% | 1.88% | 80.67% |\n\
+             | #2 | 314904 | 32116 | 18.39% | 1.88% | 19.33% |\n",
+            heads_up(&hup, &[], Format::Markdown).unwrap()
+        );
+    }
+
+    #[test]
+    fn heads_up__text() {
+        let hup = HeadsUp::new(1_365_284, 314_904, 32_116);
+
+        assert_eq!(
+            "79.73% (1365284), 18.39% (314904), 1.88% (32116)\n",
+            heads_up(&hup, &[], Format::Text).unwrap()
+        );
+    }
+
+    #[test]
+    fn heads_up__labels() {
+        let hup = HeadsUp::new(1_365_284, 314_904, 32_116);
+        let labels = vec!["Negreanu".to_string(), "Hansen".to_string()];
+
+        let json: serde_json::Value =
+            serde_json::from_str(&heads_up(&hup, &labels, Format::Json).unwrap()).unwrap();
+
+        assert_eq!(
+            "Negreanu 79.73% (1365284), Hansen 18.39% (314904), ties 1.88% (32116)\n",
+            heads_up(&hup, &labels, Format::Text).unwrap()
+        );
+        assert!(heads_up(&hup, &labels, Format::Markdown)
+            .unwrap()
+            .contains("| Hansen | 314904 |"));
+        assert_eq!("Hansen", json["labels"][1]);
+        assert_eq!(314_904, json["second_wins"]);
+    }
+
+    #[test]
+    fn results__markdown__no_winner() {
+        let results = WinResults::from(&HeadsUp::new(2, 1, 0).with_no_winner(1));
+
+        assert!(super::results(&results, Format::Markdown)
+            .unwrap()
+            .ends_with(
+                "| #2 | 1 | 0 | 25.00% | 0.00% | 25.00% |\n| No winner | 1 | | 25.00% | | |\n"
+            ));
+    }
+
+    #[test]
+    fn head_to_head__markdown() {
+        let mut wins = wincounter::wins::Wins::default();
+        wins.add_x(0b01, 3);
+        wins.add_x(0b10, 1);
+
+        assert_eq!(
+            "| | A | #2 |\n\
+             |---|---:|---:|\n\
+             | A | - | 75.00% |\n\
+             | #2 | 25.00% | - |\n",
+            head_to_head(&wins.head_to_head(2).with_labels(["A"]), Format::Markdown).unwrap()
+        );
+    }
+
+    #[test]
+    fn results__json() {
+        let results = WinResults::from(&HeadsUp::new(3, 1, 0));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&super::results(&results, Format::Json).unwrap()).unwrap();
+
+        assert_eq!(4, json["case_count"]);
+        assert_eq!(3, json["players"][0]["wins"]);
+    }
+}
@@ -0,0 +1,812 @@
+//! A compact binary format for [`Wins`], for runs that are too big to be kept around as text.
+//!
+//! Every file starts with a twelve byte header:
+//!
+//! | bytes | what                                                         |
+//! |-------|--------------------------------------------------------------|
+//! | 0..4  | the magic number `WCNT`                                      |
+//! | 4     | the format version, currently [`BINARY_VERSION`]             |
+//! | 5     | the [`Layout`] of the records that follow                    |
+//! | 6     | the flag width in bytes (`1` for `u8` up to `16` for `u128`) |
+//! | 7     | option bits, see below                                       |
+//! | 8..12 | the player count, little endian `u32`                        |
+//!
+//! Bit 0 of the options is [`NO_WINNER`]. When it's set, a record with a `0` pattern is an
+//! outcome nobody won, and is read back into [`Wins::no_winner()`]. Files from before it existed
+//! have it clear, so their `0` patterns are still read back as plain `0` patterns. Every file
+//! written now sets it, so the [`Writer`] won't take a `0` pattern from anywhere but
+//! [`add_no_winner_x()`](Writer::add_no_winner_x), rather than have it come back as no winner.
+//! The rest of the bits are reserved and always `0`.
+//!
+//! Version 2 files have the players' labels straight after the header: a `u32` count of them,
+//! then each one as a `u32` length in bytes followed by its UTF-8. A file without labels is
+//! still written as version 1, so older readers can keep reading it.
+//!
+//! After that it's nothing but records, all little endian, until the end of the file:
+//!
+//! * [`Layout::Tally`] - a flag pattern followed by a `u64` count. The same pattern can show up
+//!   more than once, which is what lets a long run checkpoint as it goes. The counts are added
+//!   together when it's read back in.
+//! * [`Layout::Flags`] - one flag pattern per outcome, as it happened, with no counts at all.
+//!
+//! Neither the [`Writer`] nor the [`Reader`] ever hold more than one record in memory, so
+//! files can be merged into a running `Wins` without every flag being loaded at once.
+//!
+//! ```
+//! use wincounter::binary::{Layout, Reader, Writer};
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let mut writer = Writer::new(Vec::new(), Layout::Tally, 2).unwrap();
+//! writer.add_x(Win::FIRST, 80).unwrap();
+//! writer.add_x(Win::FIRST | Win::SECOND, 5).unwrap();
+//! writer.add_x(Win::FIRST, 20).unwrap();
+//! let bytes = writer.finish().unwrap();
+//!
+//! let reader: Reader<_> = Reader::new(bytes.as_slice()).unwrap();
+//! assert_eq!(2, reader.player_count());
+//!
+//! let wins: Wins = reader.into_wins().unwrap();
+//! assert_eq!(100, wins.count(Win::FIRST));
+//! ```
+use crate::flag::Flag;
+use crate::tally::Tally;
+use crate::wins::Wins;
+use crate::PlayerFlag;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+pub const MAGIC: [u8; 4] = *b"WCNT";
+
+/// The current version of the binary format.
+pub const BINARY_VERSION: u8 = 2;
+
+const HEADER_LEN: usize = 12;
+
+/// The option bit for `0` patterns being outcomes nobody won.
+pub const NO_WINNER: u8 = 0b1;
+
+/// How the records after the header are laid out.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Layout {
+    /// A pattern and a count per record.
+    #[default]
+    Tally,
+    /// A single pattern per record, one per outcome.
+    Flags,
+}
+
+impl Layout {
+    fn to_byte(self) -> u8 {
+        match self {
+            Layout::Tally => 0,
+            Layout::Flags => 1,
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(Layout::Tally),
+            1 => Some(Layout::Flags),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BinaryError {
+    Io(io::Error),
+    /// The first four bytes weren't `WCNT`, so this isn't one of ours.
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownLayout(u8),
+    /// A width that isn't a whole number of bytes from 1 to 16.
+    BadWidth(u8),
+    /// The pattern was saved from a wider flag than the one it's being read into.
+    PatternTooWide(u128),
+    /// A count that doesn't fit in this platform's `usize`.
+    CountTooLarge(u64),
+    /// A label that isn't UTF-8.
+    BadLabel,
+    /// The file ended partway through the header or a record.
+    Truncated,
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(e) => write!(f, "{e}"),
+            BinaryError::BadMagic => write!(f, "not a wincounter binary file"),
+            BinaryError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported binary version {v} (this crate reads up to {BINARY_VERSION})"
+            ),
+            BinaryError::UnknownLayout(b) => write!(f, "unknown record layout {b}"),
+            BinaryError::BadWidth(w) => write!(f, "bad flag width of {w} bytes"),
+            BinaryError::PatternTooWide(p) => write!(f, "pattern {p:#b} doesn't fit in the flag"),
+            BinaryError::CountTooLarge(x) => write!(f, "count {x} doesn't fit in a usize"),
+            BinaryError::BadLabel => write!(f, "a player label isn't valid UTF-8"),
+            BinaryError::Truncated => write!(f, "file ends in the middle of a record"),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BinaryError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(e: io::Error) -> Self {
+        BinaryError::Io(e)
+    }
+}
+
+/// Streams records out as they're added. Wrap the inner writer in a `BufWriter` if it's a file;
+/// the [`Wins::write_binary()`] shorthand does that for you.
+#[derive(Debug)]
+pub struct Writer<W: Write, F: Flag = PlayerFlag> {
+    inner: W,
+    layout: Layout,
+    phantom: PhantomData<F>,
+}
+
+impl<W: Write, F: Flag> Writer<W, F> {
+    /// Writes the header straight away.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the header can't be written, or if `player_count` is more than a
+    /// `u32` can hold.
+    pub fn new(inner: W, layout: Layout, player_count: usize) -> io::Result<Self> {
+        Self::with_labels(inner, layout, player_count, &[])
+    }
+
+    /// Writes the header and the players' labels straight away.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the header can't be written, or if `player_count`, the number of
+    /// labels, or the length of one is more than a `u32` can hold.
+    pub fn with_labels(
+        mut inner: W,
+        layout: Layout,
+        player_count: usize,
+        labels: &[String],
+    ) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        header[0..4].copy_from_slice(&MAGIC);
+        header[4] = if labels.is_empty() { 1 } else { BINARY_VERSION };
+        header[5] = layout.to_byte();
+        header[6] = width::<F>();
+        header[7] = NO_WINNER;
+        header[8..12].copy_from_slice(&to_u32(player_count)?.to_le_bytes());
+        inner.write_all(&header)?;
+        if !labels.is_empty() {
+            inner.write_all(&to_u32(labels.len())?.to_le_bytes())?;
+            for label in labels {
+                inner.write_all(&to_u32(label.len())?.to_le_bytes())?;
+                inner.write_all(label.as_bytes())?;
+            }
+        }
+        Ok(Self {
+            inner,
+            layout,
+            phantom: PhantomData,
+        })
+    }
+
+    #[must_use]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the record can't be written.
+    pub fn add(&mut self, count: F) -> io::Result<()> {
+        self.add_x(count, 1)
+    }
+
+    /// With `Layout::Flags` this writes the pattern `x` times, since there's nowhere to put a
+    /// count.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the record can't be written, or one of kind `InvalidInput` for a
+    /// `0` pattern, which would be read back as no winner.
+    pub fn add_x(&mut self, count: F, x: usize) -> io::Result<()> {
+        if count.is_none() && x > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a `0` pattern would be read back as no winner",
+            ));
+        }
+        self.write_x(count, x)
+    }
+
+    /// Outcomes nobody won are written as `0` patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the record can't be written.
+    pub fn add_no_winner_x(&mut self, x: usize) -> io::Result<()> {
+        self.write_x(F::NONE, x)
+    }
+
+    fn write_x(&mut self, count: F, x: usize) -> io::Result<()> {
+        if x == 0 {
+            return Ok(());
+        }
+        let pattern = count.to_u128().to_le_bytes();
+        let pattern = &pattern[..usize::from(width::<F>())];
+        match self.layout {
+            Layout::Tally => {
+                self.inner.write_all(pattern)?;
+                self.inner.write_all(&(x as u64).to_le_bytes())
+            },
+            Layout::Flags => {
+                for _ in 0..x {
+                    self.inner.write_all(pattern)?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Writes every pattern in a `Wins`, and its no-winner count, which is how a running tally
+    /// gets checkpointed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if a record can't be written, or if the `Wins` has a `0` pattern, as
+    /// with [`add_x()`](Writer::add_x).
+    pub fn extend(&mut self, wins: &Wins<F>) -> io::Result<()> {
+        for (count, x) in wins.iter() {
+            self.add_x(count, x)?;
+        }
+        self.add_no_winner_x(wins.no_winner())
+    }
+
+    /// Flushes and hands back the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the flush fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads records back one at a time. As an `Iterator` it hands back `(pattern, count)` pairs,
+/// with a count of one for every record in a `Layout::Flags` file.
+///
+/// Files can be read into a wider flag than they were written from, but not a narrower one
+/// unless every pattern happens to fit.
+#[derive(Debug)]
+pub struct Reader<R: Read, F: Flag = PlayerFlag> {
+    inner: R,
+    layout: Layout,
+    width: u8,
+    player_count: usize,
+    labels: Vec<String>,
+    options: u8,
+    done: bool,
+    phantom: PhantomData<F>,
+}
+
+impl<R: Read, F: Flag> Reader<R, F> {
+    /// Reads and checks the header.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BinaryError` if the header is missing, isn't ours, or is from a version of the
+    /// format this crate doesn't know about.
+    pub fn new(mut inner: R) -> Result<Self, BinaryError> {
+        let mut header = [0u8; HEADER_LEN];
+        if !fill(&mut inner, &mut header)? {
+            return Err(BinaryError::Truncated);
+        }
+        if header[0..4] != MAGIC {
+            return Err(BinaryError::BadMagic);
+        }
+        if header[4] == 0 || header[4] > BINARY_VERSION {
+            return Err(BinaryError::UnsupportedVersion(header[4]));
+        }
+        let layout = Layout::from_byte(header[5]).ok_or(BinaryError::UnknownLayout(header[5]))?;
+        let width = header[6];
+        if width == 0 || width > 16 {
+            return Err(BinaryError::BadWidth(width));
+        }
+        let player_count = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        let labels = if header[4] >= 2 {
+            read_labels(&mut inner)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            inner,
+            layout,
+            width,
+            player_count: player_count as usize,
+            labels,
+            options: header[7],
+            done: false,
+            phantom: PhantomData,
+        })
+    }
+
+    #[must_use]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    #[must_use]
+    pub fn player_count(&self) -> usize {
+        self.player_count
+    }
+
+    /// Empty for a version 1 file, or one saved without labels.
+    #[must_use]
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// True if the file's `0` patterns are outcomes nobody won. The iterator still hands them
+    /// back as `0` patterns either way; [`read_into()`](Reader::read_into) sorts them out.
+    #[must_use]
+    pub fn has_no_winner(&self) -> bool {
+        self.options & NO_WINNER != 0
+    }
+
+    /// The width of the flags in the file, in bits.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        usize::from(self.width) * 8
+    }
+
+    /// Adds everything left in the file to `wins`, for merging checkpoints into a running total.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `BinaryError` hit while reading. Anything read before it has already
+    /// been added.
+    pub fn read_into(self, wins: &mut Wins<F>) -> Result<(), BinaryError> {
+        let no_winner = self.has_no_winner();
+        for record in self {
+            let (count, x) = record?;
+            if no_winner && count.is_none() {
+                wins.add_no_winner_x(x);
+            } else {
+                wins.add_x(count, x);
+            }
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Same as [`read_into()`](Reader::read_into).
+    pub fn into_wins(self) -> Result<Wins<F>, BinaryError> {
+        let mut wins = Wins::new();
+        self.read_into(&mut wins)?;
+        Ok(wins)
+    }
+
+    fn next_record(&mut self) -> Result<Option<(F, usize)>, BinaryError> {
+        let mut pattern = [0u8; 16];
+        if !fill(&mut self.inner, &mut pattern[..usize::from(self.width)])? {
+            return Ok(None);
+        }
+        let pattern = u128::from_le_bytes(pattern);
+        let count = F::from_u128(pattern).ok_or(BinaryError::PatternTooWide(pattern))?;
+        let x = match self.layout {
+            Layout::Tally => {
+                let mut x = [0u8; 8];
+                if !fill(&mut self.inner, &mut x)? {
+                    return Err(BinaryError::Truncated);
+                }
+                let x = u64::from_le_bytes(x);
+                usize::try_from(x).map_err(|_| BinaryError::CountTooLarge(x))?
+            },
+            Layout::Flags => 1,
+        };
+        Ok(Some((count, x)))
+    }
+}
+
+impl<R: Read, F: Flag> Iterator for Reader<R, F> {
+    type Item = Result<(F, usize), BinaryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.next_record().transpose();
+        if !matches!(record, Some(Ok(_))) {
+            self.done = true;
+        }
+        record
+    }
+}
+
+impl<F: Flag> Wins<F> {
+    /// Saves as a `Layout::Tally` binary file. See the [module docs](crate::binary).
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the file can't be written, or if there's a `0` pattern, as with
+    /// [`Writer::add_x()`].
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_binary<P: AsRef<Path>>(&self, path: P, player_count: usize) -> io::Result<()> {
+        let file = io::BufWriter::new(File::create(path)?);
+        let mut writer = Writer::new(file, Layout::Tally, player_count)?;
+        writer.extend(self)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Loads a binary file of either layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BinaryError` if the file can't be opened or read.
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_binary<P: AsRef<Path>>(path: P) -> Result<Wins<F>, BinaryError> {
+        Reader::new(io::BufReader::new(File::open(path)?))?.into_wins()
+    }
+}
+
+impl<F: Flag> Tally<F> {
+    /// Saves as a `Layout::Tally` binary file, with the labels if there are any. Without a
+    /// player count, the highest player seen is saved instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` if the file can't be written, or if there's a `0` pattern, as with
+    /// [`Writer::add_x()`].
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_binary<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let file = io::BufWriter::new(File::create(path)?);
+        let player_count = self.player_count.unwrap_or_else(|| self.highest_player());
+        let mut writer = Writer::with_labels(file, Layout::Tally, player_count, &self.labels)?;
+        writer.extend(&self.wins)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Loads a binary file of either layout, along with its player count and labels.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BinaryError` if the file can't be opened or read.
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_binary<P: AsRef<Path>>(path: P) -> Result<Tally<F>, BinaryError> {
+        Tally::try_from(Reader::new(io::BufReader::new(File::open(path)?))?)
+    }
+}
+
+/// Reads everything that's left, keeping the player count and labels from the header.
+impl<R: Read, F: Flag> TryFrom<Reader<R, F>> for Tally<F> {
+    type Error = BinaryError;
+
+    fn try_from(mut reader: Reader<R, F>) -> Result<Self, Self::Error> {
+        let player_count = reader.player_count;
+        let labels = std::mem::take(&mut reader.labels);
+        Ok(Tally::new(reader.into_wins()?, player_count).with_labels(labels))
+    }
+}
+
+fn to_u32(n: usize) -> io::Result<u32> {
+    u32::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too large"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, BinaryError> {
+    let mut bytes = [0u8; 4];
+    if fill(reader, &mut bytes)? {
+        Ok(u32::from_le_bytes(bytes))
+    } else {
+        Err(BinaryError::Truncated)
+    }
+}
+
+fn read_labels<R: Read>(reader: &mut R) -> Result<Vec<String>, BinaryError> {
+    let count = read_u32(reader)?;
+    let mut labels = Vec::new();
+    for _ in 0..count {
+        // The length comes from the file, so it's only trusted as far as the bytes are there.
+        let len = u64::from(read_u32(reader)?);
+        let mut bytes = Vec::new();
+        reader.by_ref().take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(BinaryError::Truncated);
+        }
+        labels.push(String::from_utf8(bytes).map_err(|_| BinaryError::BadLabel)?);
+    }
+    Ok(labels)
+}
+
+fn width<F: Flag>() -> u8 {
+    // CAPACITY tops out at 128, so this always fits.
+    u8::try_from(F::CAPACITY / 8).unwrap_or(16)
+}
+
+/// Fills `buf`, returning `false` if the reader was already at the end, and
+/// `BinaryError::Truncated` if it ran out partway through.
+fn fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, BinaryError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(BinaryError::Truncated),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__binary__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn the_hand_as_wins() -> Wins {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 1_365_284);
+        wins.add_x(Win::SECOND, 314_904);
+        wins.add_x(Win::FIRST | Win::SECOND, 32_116);
+        wins
+    }
+
+    fn tally_bytes(wins: &Wins) -> Vec<u8> {
+        let mut writer = Writer::new(Vec::new(), Layout::Tally, 2).unwrap();
+        writer.extend(wins).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn writer__header() {
+        let bytes = Writer::<_, u32>::new(Vec::new(), Layout::Flags, 9)
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        assert_eq!(b"WCNT\x01\x01\x04\x01\x09\x00\x00\x00".to_vec(), bytes);
+    }
+
+    #[test]
+    fn no_winner__round_trip() {
+        let mut wins = the_hand_as_wins();
+        wins.add_no_winner_x(11);
+
+        let bytes = tally_bytes(&wins);
+        let reader: Reader<_> = Reader::new(bytes.as_slice()).unwrap();
+
+        assert!(reader.has_no_winner());
+        assert_eq!(wins, reader.into_wins().unwrap());
+    }
+
+    #[test]
+    fn no_winner__zero_patterns() {
+        let zero = Wins::from(vec![0]);
+        let mut writer = Writer::new(Vec::new(), Layout::Tally, 2).unwrap();
+
+        let error = writer.extend(&zero).unwrap_err();
+
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+        writer.add_no_winner_x(1).unwrap();
+        let wins: Wins = Reader::new(writer.finish().unwrap().as_slice())
+            .unwrap()
+            .into_wins()
+            .unwrap();
+        assert_eq!(1, wins.no_winner());
+        assert_eq!(0, wins.count(0));
+        assert_ne!(zero, wins);
+    }
+
+    #[test]
+    fn no_winner__older_files() {
+        let mut bytes = b"WCNT\x01\x00\x02\x00\x02\x00\x00\x00".to_vec();
+        bytes.extend_from_slice(&[0, 0, 4, 0, 0, 0, 0, 0, 0, 0]);
+
+        let reader: Reader<_> = Reader::new(bytes.as_slice()).unwrap();
+
+        assert!(!reader.has_no_winner());
+        let wins = reader.into_wins().unwrap();
+        assert_eq!(4, wins.count(0));
+        assert_eq!(0, wins.no_winner());
+    }
+
+    #[test]
+    fn tally__round_trip() {
+        let wins = the_hand_as_wins();
+
+        let bytes = tally_bytes(&wins);
+        let reader: Reader<_> = Reader::new(bytes.as_slice()).unwrap();
+
+        assert_eq!(HEADER_LEN + 3 * (2 + 8), bytes.len());
+        assert_eq!(Layout::Tally, reader.layout());
+        assert_eq!(2, reader.player_count());
+        assert_eq!(16, reader.width());
+        assert_eq!(wins, reader.into_wins().unwrap());
+    }
+
+    #[test]
+    fn flags__round_trip() {
+        let mut writer = Writer::new(Vec::new(), Layout::Flags, 3).unwrap();
+        writer.add(Win::FIRST).unwrap();
+        writer.add_x(Win::SECOND | Win::THIRD, 2).unwrap();
+        writer.add_x(Win::THIRD, 0).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let records: Vec<(u16, usize)> = Reader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(HEADER_LEN + 3 * 2, bytes.len());
+        assert_eq!(
+            vec![
+                (Win::FIRST, 1),
+                (Win::SECOND | Win::THIRD, 1),
+                (Win::SECOND | Win::THIRD, 1)
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn read_into__merges_checkpoints() {
+        let wins = the_hand_as_wins();
+        let mut total = Wins::default();
+
+        Reader::new(tally_bytes(&wins).as_slice())
+            .unwrap()
+            .read_into(&mut total)
+            .unwrap();
+        Reader::new(tally_bytes(&wins).as_slice())
+            .unwrap()
+            .read_into(&mut total)
+            .unwrap();
+
+        assert_eq!(2 * 1_365_284, total.count(Win::FIRST));
+        assert_eq!(2 * wins.len(), total.len());
+    }
+
+    #[test]
+    fn reader__widths() {
+        let mut writer = Writer::<_, u64>::new(Vec::new(), Layout::Tally, 40).unwrap();
+        writer.add_x(1 << 39, 3).unwrap();
+        writer.add(1).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let wider: Wins<u128> = Reader::new(bytes.as_slice()).unwrap().into_wins().unwrap();
+        let narrower = Reader::<_, u16>::new(bytes.as_slice()).unwrap().into_wins();
+
+        assert_eq!(3, wider.count(1 << 39));
+        assert!(matches!(narrower, Err(BinaryError::PatternTooWide(_))));
+    }
+
+    #[test]
+    fn reader__errors() {
+        let bytes = tally_bytes(&the_hand_as_wins());
+
+        assert!(matches!(
+            Reader::<_, u16>::new(&b"WCN"[..]),
+            Err(BinaryError::Truncated)
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&b"NOPE\x01\x00\x02\x00\x02\x00\x00\x00"[..]),
+            Err(BinaryError::BadMagic)
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&b"WCNT\x03\x00\x02\x00\x02\x00\x00\x00"[..]),
+            Err(BinaryError::UnsupportedVersion(3))
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&b"WCNT\x01\x07\x02\x00\x02\x00\x00\x00"[..]),
+            Err(BinaryError::UnknownLayout(7))
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&b"WCNT\x01\x00\x11\x00\x02\x00\x00\x00"[..]),
+            Err(BinaryError::BadWidth(17))
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&bytes[..bytes.len() - 1])
+                .unwrap()
+                .into_wins(),
+            Err(BinaryError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn reader__stops_after_error() {
+        let bytes = tally_bytes(&the_hand_as_wins());
+        let mut reader = Reader::<_, u16>::new(&bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn labels__round_trip() {
+        let labels = vec!["Negreanu".to_string(), String::new(), "A♠ K♠".to_string()];
+        let mut writer = Writer::with_labels(Vec::new(), Layout::Tally, 3, &labels).unwrap();
+        writer.add(Win::THIRD).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let reader: Reader<_> = Reader::new(bytes.as_slice()).unwrap();
+
+        assert_eq!(2, bytes[4]);
+        assert_eq!(labels, reader.labels());
+        assert_eq!(1, reader.into_wins().unwrap().count(Win::THIRD));
+    }
+
+    #[test]
+    fn labels__errors() {
+        let truncated = b"WCNT\x02\x00\x02\x00\x02\x00\x00\x00\x01\x00\x00\x00\x05\x00\x00\x00ab";
+        let not_utf8 = b"WCNT\x02\x00\x02\x00\x02\x00\x00\x00\x01\x00\x00\x00\x01\x00\x00\x00\xff";
+        let huge = b"WCNT\x02\x00\x02\x00\x02\x00\x00\x00\x01\x00\x00\x00\xff\xff\xff\xffab";
+
+        assert!(matches!(
+            Reader::<_, u16>::new(&truncated[..]),
+            Err(BinaryError::Truncated)
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&huge[..]),
+            Err(BinaryError::Truncated)
+        ));
+        assert!(matches!(
+            Reader::<_, u16>::new(&not_utf8[..]),
+            Err(BinaryError::BadLabel)
+        ));
+    }
+
+    #[test]
+    fn tally__files() {
+        let path = std::env::temp_dir().join("wincounter__binary__tests__tally.wcnt");
+        let tally = Tally::new(the_hand_as_wins(), 2).with_labels(["Negreanu", "Hansen"]);
+
+        tally.write_binary(&path).unwrap();
+
+        assert_eq!(tally, Tally::read_binary(&path).unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn files() {
+        let path = std::env::temp_dir().join("wincounter__binary__tests.wcnt");
+        let wins = the_hand_as_wins();
+
+        wins.write_binary(&path, 2).unwrap();
+
+        assert_eq!(wins, Wins::read_binary(&path).unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+use crate::flag::Flag;
+use crate::heads_up::HeadsUp;
+use crate::results::WinResults;
+use crate::wins::Wins;
+use crate::PlayerFlag;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{PoisonError, RwLock};
+
+static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Each thread gets a number the first time it records anything, which decides its shard.
+    static THREAD_INDEX: usize = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+type Shard<F> = RwLock<BTreeMap<F, AtomicUsize>>;
+
+/// A win counter that any number of threads can record into at the same time.
+///
+/// Before this, every thread needed its own `Wins`, and they all had to be stitched together
+/// with `Wins::extend()` at the end. `ConcurrentWins` splits its counts across shards, one per
+/// core by default, and gives each thread its own shard, so they aren't all fighting over the
+/// same counters. Each shard holds an atomic count per `PlayerFlag` pattern. Recording a pattern
+/// that's already been seen only needs a shared read lock, so writers don't block each other,
+/// and taking a [`snapshot`](ConcurrentWins::snapshot) doesn't block the writers either. The
+/// only time anybody waits is the first time a shard sees a new pattern. Outcomes nobody won
+/// go into a single atomic counter of their own.
+///
+/// ```
+/// use wincounter::concurrent::ConcurrentWins;
+/// use wincounter::win::Win;
+///
+/// let counter = ConcurrentWins::default();
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             counter.add_x(Win::FIRST, 80);
+///             counter.add_x(Win::SECOND, 15);
+///             counter.add_x(Win::FIRST | Win::SECOND, 5);
+///         });
+///     }
+/// });
+///
+/// let results = counter.results(2);
+/// assert_eq!(400, results.case_count);
+/// assert_eq!((320, 20), results.wins_and_ties(0));
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentWins<F: Flag = PlayerFlag> {
+    shards: Vec<Shard<F>>,
+    no_winner: AtomicUsize,
+}
+
+impl<F: Flag> ConcurrentWins<F> {
+    /// One shard per available core.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_shards(std::thread::available_parallelism().map_or(1, std::num::NonZero::get))
+    }
+
+    /// Zero shards isn't a thing, so it's bumped up to one.
+    #[must_use]
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(BTreeMap::new()))
+                .collect(),
+            no_winner: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn add(&self, count: F) {
+        self.add_x(count, 1);
+    }
+
+    pub fn add_x(&self, count: F, x: usize) {
+        if x == 0 {
+            return;
+        }
+        let shard = &self.shards[THREAD_INDEX.with(|i| *i) % self.shards.len()];
+        {
+            let counts = shard.read().unwrap_or_else(PoisonError::into_inner);
+            if let Some(n) = counts.get(&count) {
+                n.fetch_add(x, Ordering::Relaxed);
+                return;
+            }
+        }
+        shard
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(count)
+            .or_default()
+            .fetch_add(x, Ordering::Relaxed);
+    }
+
+    pub fn add_no_winner(&self) {
+        self.add_no_winner_x(1);
+    }
+
+    pub fn add_no_winner_x(&self, x: usize) {
+        self.no_winner.fetch_add(x, Ordering::Relaxed);
+    }
+
+    /// Adds everything from a `Wins`, for threads that would rather tally locally and hand off
+    /// their counts in batches.
+    pub fn extend(&self, wins: &Wins<F>) {
+        for (count, x) in wins.iter() {
+            self.add_x(count, x);
+        }
+        self.add_no_winner_x(wins.no_winner());
+    }
+
+    /// The total number of outcomes recorded so far, including the ones nobody won.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .values()
+                    .map(|n| n.load(Ordering::Relaxed))
+                    .sum::<usize>()
+            })
+            .sum::<usize>()
+            + self.no_winner.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies the counts so far into a regular `Wins`. Writers keep going while this happens,
+    /// so anything they record in the meantime may or may not make it in.
+    #[must_use]
+    pub fn snapshot(&self) -> Wins<F> {
+        let mut wins = Wins::new();
+        for shard in &self.shards {
+            let counts = shard.read().unwrap_or_else(PoisonError::into_inner);
+            for (count, n) in counts.iter() {
+                wins.add_x(*count, n.load(Ordering::Relaxed));
+            }
+        }
+        wins.add_no_winner_x(self.no_winner.load(Ordering::Relaxed));
+        wins
+    }
+
+    /// Shorthand for `WinResults::from_wins(&self.snapshot(), player_count)`.
+    #[must_use]
+    pub fn results(&self, player_count: usize) -> WinResults {
+        self.snapshot().results(player_count)
+    }
+
+    /// Shorthand for `self.snapshot().results_heads_up()`.
+    #[must_use]
+    pub fn results_heads_up(&self) -> HeadsUp {
+        self.snapshot().results_heads_up()
+    }
+
+    /// Hands back everything recorded as a `Wins`, once the writers are done with it.
+    #[must_use]
+    pub fn into_wins(self) -> Wins<F> {
+        self.snapshot()
+    }
+}
+
+impl Default for ConcurrentWins {
+    fn default() -> Self {
+        ConcurrentWins::new()
+    }
+}
+
+impl<F: Flag> From<&Wins<F>> for ConcurrentWins<F> {
+    fn from(wins: &Wins<F>) -> Self {
+        let counter = ConcurrentWins::new();
+        counter.extend(wins);
+        counter
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__concurrent__tests {
+    use super::*;
+    use crate::win::Win;
+    use std::sync::Arc;
+
+    #[test]
+    fn add() {
+        let counter = ConcurrentWins::default();
+
+        counter.add(Win::FIRST);
+        counter.add(Win::FIRST | Win::SECOND);
+        counter.add(Win::FIRST);
+
+        assert_eq!(3, counter.len());
+        assert_eq!(2, counter.snapshot().count(Win::FIRST));
+    }
+
+    #[test]
+    fn add_no_winner() {
+        let mut wins = Wins::default();
+        wins.add_no_winner_x(2);
+        let counter = ConcurrentWins::from(&wins);
+
+        counter.add_no_winner();
+        counter.add(Win::FIRST);
+
+        assert_eq!(4, counter.len());
+        assert_eq!(3, counter.snapshot().no_winner());
+        assert_eq!(3, counter.results(1).no_winner);
+    }
+
+    #[test]
+    fn add_x__zero() {
+        let counter = ConcurrentWins::default();
+
+        counter.add_x(Win::FIRST, 0);
+
+        assert!(counter.is_empty());
+        assert_eq!(0, counter.snapshot().patterns());
+    }
+
+    #[test]
+    fn with_shards() {
+        assert_eq!(1, ConcurrentWins::<u8>::with_shards(0).shards.len());
+        assert_eq!(7, ConcurrentWins::<u8>::with_shards(7).shards.len());
+    }
+
+    #[test]
+    fn threads() {
+        let counter = Arc::new(ConcurrentWins::<u32>::with_shards(3));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                std::thread::spawn(move || {
+                    for i in 0..10_000_u32 {
+                        counter.add(1 << (i % 20));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let results = counter.results(20);
+
+        assert_eq!(80_000, results.case_count);
+        for i in 0..20 {
+            assert_eq!((4_000, 0), results.wins_and_ties(i));
+        }
+    }
+
+    #[test]
+    fn snapshot__while_writing() {
+        let counter = ConcurrentWins::default();
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..50_000 {
+                    counter.add(Win::FIRST);
+                }
+            });
+            s.spawn(|| {
+                let mut last = 0;
+                for _ in 0..100 {
+                    let seen = counter.snapshot().len();
+                    assert!(seen >= last);
+                    last = seen;
+                }
+            });
+        });
+
+        assert_eq!(50_000, counter.len());
+    }
+
+    #[test]
+    fn results_heads_up() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 1_365_284);
+        wins.add_x(Win::SECOND, 314_904);
+        wins.add_x(Win::FIRST | Win::SECOND, 32_116);
+
+        let counter = ConcurrentWins::from(&wins);
+
+        assert_eq!(wins.results_heads_up(), counter.results_heads_up());
+        assert_eq!(wins, counter.into_wins());
+    }
+}
@@ -0,0 +1,445 @@
+use std::fmt::{Display, Formatter};
+
+/// How to work out a [`ConfidenceInterval`] around a rate.
+///
+/// * `Normal` - The textbook `p ± z·√(p(1-p)/n)`. Fine for big samples with rates that aren't
+///   too close to 0% or 100%, and clamped to that range when they are.
+/// * `Wilson` - The Wilson score interval. Behaves well at the edges and with small samples,
+///   which is why it's the default.
+/// * `ClopperPearson` - The "exact" interval from the beta distribution. Never too narrow, but
+///   usually wider than it needs to be.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum IntervalMethod {
+    Normal,
+    #[default]
+    Wilson,
+    ClopperPearson,
+}
+
+/// A rate from a sample of outcomes, along with its standard error and a confidence interval
+/// around it. Everything is stored as a percentage, same as the rest of the crate.
+///
+/// ```
+/// use wincounter::confidence::{ConfidenceInterval, IntervalMethod};
+///
+/// let ci = ConfidenceInterval::binomial(80.0, 100, 0.95, IntervalMethod::Normal).unwrap();
+///
+/// assert_eq!(80.0, ci.estimate);
+/// assert_eq!(4.0, ci.standard_error);
+/// assert_eq!("80.00% ± 7.84% [72.16%, 87.84%]", ci.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub standard_error: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    /// Interval for `successes` out of `trials`, at a `confidence` level between 0 and 1 (0.95
+    /// for 95%). Successes can be fractional, which is how equity gets fed in. Returns `None`
+    /// if the confidence level isn't strictly between 0 and 1.
+    ///
+    /// With no trials there's nothing to go on, so the interval covers everything from 0% to
+    /// 100%.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn binomial(
+        successes: f64,
+        trials: usize,
+        confidence: f64,
+        method: IntervalMethod,
+    ) -> Option<ConfidenceInterval> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return None;
+        }
+        if trials == 0 {
+            return Some(ConfidenceInterval {
+                upper: 100.0,
+                ..Default::default()
+            });
+        }
+        let n = trials as f64;
+        let x = successes.clamp(0.0, n);
+        let p = x / n;
+        let se = (p * (1.0 - p) / n).sqrt();
+        let alpha = 1.0 - confidence;
+        let z = inverse_normal_cdf(1.0 - alpha / 2.0);
+
+        let (lower, upper) = match method {
+            IntervalMethod::Normal => (p - z * se, p + z * se),
+            IntervalMethod::Wilson => {
+                let z2 = z * z;
+                let denominator = 1.0 + z2 / n;
+                let center = (p + z2 / (2.0 * n)) / denominator;
+                let half = z / denominator * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+                (center - half, center + half)
+            },
+            IntervalMethod::ClopperPearson => {
+                let lower = if x <= 0.0 {
+                    0.0
+                } else {
+                    inverse_beta(alpha / 2.0, x, n - x + 1.0)
+                };
+                let upper = if x >= n {
+                    1.0
+                } else {
+                    inverse_beta(1.0 - alpha / 2.0, x + 1.0, n - x)
+                };
+                (lower, upper)
+            },
+        };
+
+        Some(ConfidenceInterval {
+            estimate: p * 100.0,
+            standard_error: se * 100.0,
+            lower: lower.clamp(0.0, 1.0) * 100.0,
+            upper: upper.clamp(0.0, 1.0) * 100.0,
+        })
+    }
+
+    /// Normal interval around a percentage with a known standard error (also a percentage),
+    /// clamped to between 0% and 100%. Returns `None` if the confidence level isn't strictly
+    /// between 0 and 1.
+    #[must_use]
+    pub fn normal(
+        estimate: f64,
+        standard_error: f64,
+        confidence: f64,
+    ) -> Option<ConfidenceInterval> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return None;
+        }
+        let z = inverse_normal_cdf(1.0 - (1.0 - confidence) / 2.0);
+        Some(ConfidenceInterval {
+            estimate,
+            standard_error,
+            lower: (estimate - z * standard_error).clamp(0.0, 100.0),
+            upper: (estimate + z * standard_error).clamp(0.0, 100.0),
+        })
+    }
+
+    /// The bigger of the distances from the estimate to either end of the interval. This is the
+    /// `0.1%` in `79.7% ± 0.1%`.
+    #[must_use]
+    pub fn margin(&self) -> f64 {
+        (self.upper - self.estimate).max(self.estimate - self.lower)
+    }
+}
+
+impl Display for ConfidenceInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2}% ± {:.2}% [{:.2}%, {:.2}%]",
+            self.estimate,
+            self.margin(),
+            self.lower,
+            self.upper
+        )
+    }
+}
+
+/// Peter Acklam's rational approximation of the inverse of the standard normal CDF, with one
+/// step of Halley's method to polish it off.
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+
+    let x = if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    let e = 0.5 * erfc(-x / std::f64::consts::SQRT_2) - p;
+    let u = e * (2.0 * std::f64::consts::PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
+
+/// Complementary error function, from Numerical Recipes' Chebyshev fit. Good to about 1.2e-7,
+/// which is plenty for a single refinement step.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Lanczos approximation of `ln(Γ(x))`.
+fn ln_gamma(x: f64) -> f64 {
+    const G: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+    for g in G {
+        y += 1.0;
+        series += g / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Lentz's method for the continued fraction behind `incomplete_beta()`.
+#[allow(clippy::cast_precision_loss, clippy::many_single_char_names)]
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1.0e-300;
+    const EPSILON: f64 = 1.0e-14;
+    const MAX_ITERATIONS: usize = 100_000;
+
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Finds `x` where `I_x(a, b) = p` by bisection. Slow and steady, but the function is
+/// monotonic so it can't get lost.
+fn inverse_beta(p: f64, a: f64, b: f64) -> f64 {
+    let (mut low, mut high) = (0.0_f64, 1.0_f64);
+    for _ in 0..200 {
+        let mid = f64::midpoint(low, high);
+        if incomplete_beta(mid, a, b) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low < 1.0e-15 {
+            break;
+        }
+    }
+    f64::midpoint(low, high)
+}
+
+#[cfg(test)]
+#[allow(
+    non_snake_case,
+    clippy::float_cmp,
+    clippy::unreadable_literal,
+    clippy::unwrap_used
+)]
+mod util__wincounter__confidence__tests {
+    use super::*;
+
+    fn round(x: f64, places: i32) -> f64 {
+        let m = 10_f64.powi(places);
+        (x * m).round() / m
+    }
+
+    #[test]
+    fn binomial__normal() {
+        let ci = ConfidenceInterval::binomial(8.0, 10, 0.95, IntervalMethod::Normal).unwrap();
+
+        assert_eq!(80.0, ci.estimate);
+        assert_eq!(12.6491, round(ci.standard_error, 4));
+        assert_eq!(55.2082, round(ci.lower, 4));
+        assert_eq!(100.0, ci.upper);
+    }
+
+    #[test]
+    fn binomial__wilson() {
+        let ci = ConfidenceInterval::binomial(8.0, 10, 0.95, IntervalMethod::Wilson).unwrap();
+
+        assert_eq!(49.0162, round(ci.lower, 4));
+        assert_eq!(94.3318, round(ci.upper, 4));
+    }
+
+    #[test]
+    fn binomial__clopper_pearson() {
+        let ci =
+            ConfidenceInterval::binomial(8.0, 10, 0.95, IntervalMethod::ClopperPearson).unwrap();
+
+        assert_eq!(44.3905, round(ci.lower, 4));
+        assert_eq!(97.4789, round(ci.upper, 4));
+    }
+
+    #[test]
+    fn binomial__clopper_pearson__edges() {
+        let none =
+            ConfidenceInterval::binomial(0.0, 10, 0.95, IntervalMethod::ClopperPearson).unwrap();
+        let all =
+            ConfidenceInterval::binomial(10.0, 10, 0.95, IntervalMethod::ClopperPearson).unwrap();
+
+        assert_eq!(0.0, none.lower);
+        assert_eq!(30.8497, round(none.upper, 4));
+        assert_eq!(69.1503, round(all.lower, 4));
+        assert_eq!(100.0, all.upper);
+    }
+
+    #[test]
+    fn binomial__large_sample() {
+        // The Hand: Daniel wins alone 1,365,284 times out of 1,712,304.
+        let wilson =
+            ConfidenceInterval::binomial(1_365_284.0, 1_712_304, 0.95, IntervalMethod::Wilson)
+                .unwrap();
+        let exact = ConfidenceInterval::binomial(
+            1_365_284.0,
+            1_712_304,
+            0.95,
+            IntervalMethod::ClopperPearson,
+        )
+        .unwrap();
+
+        assert_eq!(0.06, round(wilson.margin(), 2));
+        assert_eq!(round(wilson.lower, 3), round(exact.lower, 3));
+        assert_eq!(round(wilson.upper, 3), round(exact.upper, 3));
+    }
+
+    #[test]
+    fn binomial__no_trials() {
+        let ci = ConfidenceInterval::binomial(0.0, 0, 0.95, IntervalMethod::Wilson).unwrap();
+
+        assert_eq!(0.0, ci.lower);
+        assert_eq!(100.0, ci.upper);
+    }
+
+    #[test]
+    fn binomial__invalid_confidence() {
+        assert!(ConfidenceInterval::binomial(1.0, 2, 0.0, IntervalMethod::Wilson).is_none());
+        assert!(ConfidenceInterval::binomial(1.0, 2, 1.0, IntervalMethod::Wilson).is_none());
+        assert!(ConfidenceInterval::binomial(1.0, 2, f64::NAN, IntervalMethod::Wilson).is_none());
+        assert!(ConfidenceInterval::normal(50.0, 1.0, 95.0).is_none());
+    }
+
+    #[test]
+    fn normal() {
+        let ci = ConfidenceInterval::normal(50.0, 1.0, 0.95).unwrap();
+
+        assert_eq!(48.04, round(ci.lower, 2));
+        assert_eq!(51.96, round(ci.upper, 2));
+        assert_eq!(1.96, round(ci.margin(), 2));
+    }
+
+    #[test]
+    fn inverse_normal_cdf() {
+        assert_eq!(1.959964, round(super::inverse_normal_cdf(0.975), 6));
+        assert_eq!(2.575829, round(super::inverse_normal_cdf(0.995), 6));
+        assert_eq!(0.0, round(super::inverse_normal_cdf(0.5), 6));
+        assert_eq!(-1.644854, round(super::inverse_normal_cdf(0.05), 6));
+    }
+
+    #[test]
+    fn display() {
+        let ci = ConfidenceInterval {
+            estimate: 79.7,
+            standard_error: 0.05,
+            lower: 79.6,
+            upper: 79.8,
+        };
+
+        assert_eq!("79.70% ± 0.10% [79.60%, 79.80%]", ci.to_string());
+    }
+}
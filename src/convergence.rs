@@ -0,0 +1,245 @@
+use crate::confidence::IntervalMethod;
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::wins::Wins;
+
+/// Where a sampling run stands according to a [`StoppingRule`]. Each variant carries the widest
+/// margin of error, in percentage points, across all of the players' equity estimates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Convergence {
+    /// Not there yet. Keep sampling.
+    Running(f64),
+    /// Every player's equity is within the target margin of error.
+    Converged(f64),
+    /// The iteration budget ran out before the results settled down.
+    Exhausted(f64),
+}
+
+impl Convergence {
+    /// True if the simulation should stop, either because it converged or ran out of budget.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        !matches!(self, Convergence::Running(_))
+    }
+
+    #[must_use]
+    pub fn margin(&self) -> f64 {
+        match self {
+            Convergence::Running(m) | Convergence::Converged(m) | Convergence::Exhausted(m) => *m,
+        }
+    }
+}
+
+/// Tells a Monte Carlo simulation when it can stop, instead of guessing at an iteration count
+/// up front.
+///
+/// The rule looks at the confidence interval around every player's
+/// [equity](WinResults::equity_percentage), and calls it done once the widest one is within
+/// `margin` percentage points, or once `max_iterations` outcomes have been recorded.
+/// `min_iterations` keeps it from stopping early on a lucky streak, when a handful of identical
+/// outcomes can look very certain.
+///
+/// ```
+/// use wincounter::convergence::{Convergence, StoppingRule};
+/// use wincounter::win::Win;
+/// use wincounter::wins::Wins;
+///
+/// // Within half a percentage point, 95% of the time.
+/// let rule = StoppingRule::new(2, 0.5);
+/// let mut wins = Wins::default();
+/// let mut i = 0_usize;
+///
+/// let convergence = rule.run(&mut wins, 1_000, || {
+///     i += 1;
+///     match i % 10 {
+///         0..=6 => Win::FIRST,
+///         7 | 8 => Win::SECOND,
+///         _ => Win::FIRST | Win::SECOND,
+///     }
+/// });
+///
+/// assert!(matches!(convergence, Convergence::Converged(_)));
+/// assert!(convergence.margin() <= 0.5);
+/// assert_eq!(0, wins.len() % 1_000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StoppingRule {
+    pub player_count: usize,
+    /// Target margin of error, in percentage points.
+    pub margin: f64,
+    /// Confidence level, between 0 and 1.
+    pub confidence: f64,
+    pub method: IntervalMethod,
+    pub min_iterations: usize,
+    pub max_iterations: usize,
+}
+
+impl StoppingRule {
+    pub const DEFAULT_CONFIDENCE: f64 = 0.95;
+    pub const DEFAULT_MIN_ITERATIONS: usize = 100;
+
+    /// A rule with a 95% confidence level, Wilson intervals, a minimum of 100 iterations, and no
+    /// maximum. Change the public fields for anything else.
+    #[must_use]
+    pub fn new(player_count: usize, margin: f64) -> Self {
+        Self {
+            player_count,
+            margin,
+            confidence: StoppingRule::DEFAULT_CONFIDENCE,
+            method: IntervalMethod::default(),
+            min_iterations: StoppingRule::DEFAULT_MIN_ITERATIONS,
+            max_iterations: usize::MAX,
+        }
+    }
+
+    /// Checks a running `Wins` against the rule.
+    ///
+    /// A confidence level outside of 0 to 1 can never be satisfied, so the run will only stop
+    /// when it hits `max_iterations`.
+    #[must_use]
+    pub fn check<F: Flag>(&self, wins: &Wins<F>) -> Convergence {
+        let results = WinResults::from_wins(wins, self.player_count);
+        let margin = self.widest_margin(&results);
+
+        if results.case_count >= self.min_iterations && margin <= self.margin {
+            Convergence::Converged(margin)
+        } else if results.case_count >= self.max_iterations {
+            Convergence::Exhausted(margin)
+        } else {
+            Convergence::Running(margin)
+        }
+    }
+
+    /// Shorthand for `check(wins).is_done()`.
+    #[must_use]
+    pub fn should_stop<F: Flag>(&self, wins: &Wins<F>) -> bool {
+        self.check(wins).is_done()
+    }
+
+    /// Drives a simulation. Calls `sample` for one outcome at a time, adding each to `wins`, and
+    /// checks the rule every `check_every` outcomes until it's done. Checking isn't free, since
+    /// it has to aggregate the results, so don't check every single iteration.
+    pub fn run<F: Flag, S: FnMut() -> F>(
+        &self,
+        wins: &mut Wins<F>,
+        check_every: usize,
+        mut sample: S,
+    ) -> Convergence {
+        let check_every = check_every.max(1);
+        loop {
+            let convergence = self.check(wins);
+            if convergence.is_done() {
+                return convergence;
+            }
+            let remaining = self.max_iterations.saturating_sub(wins.len());
+            for _ in 0..check_every.min(remaining) {
+                wins.add(sample());
+            }
+        }
+    }
+
+    fn widest_margin(&self, results: &WinResults) -> f64 {
+        (0..self.player_count)
+            .map(|i| {
+                results
+                    .equity_interval(i, self.confidence, self.method)
+                    .map_or(f64::INFINITY, |ci| ci.margin())
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp)]
+mod util__wincounter__convergence__tests {
+    use super::*;
+    use crate::win::Win;
+
+    #[test]
+    fn check__running() {
+        let rule = StoppingRule::new(2, 1.0);
+        let wins = Wins::from(vec![Win::FIRST, Win::SECOND]);
+
+        let convergence = rule.check(&wins);
+
+        assert!(matches!(convergence, Convergence::Running(_)));
+        assert!(!convergence.is_done());
+        assert!(convergence.margin() > 1.0);
+    }
+
+    #[test]
+    fn check__converged() {
+        let rule = StoppingRule::new(2, 1.0);
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 40_000);
+        wins.add_x(Win::SECOND, 40_000);
+
+        let convergence = rule.check(&wins);
+
+        assert!(matches!(convergence, Convergence::Converged(_)));
+        assert!(rule.should_stop(&wins));
+    }
+
+    #[test]
+    fn check__min_iterations() {
+        let rule = StoppingRule::new(2, 50.0);
+        let wins = Wins::from(vec![Win::FIRST; 10]);
+
+        assert!(matches!(rule.check(&wins), Convergence::Running(_)));
+    }
+
+    #[test]
+    fn check__exhausted() {
+        let rule = StoppingRule {
+            max_iterations: 10,
+            ..StoppingRule::new(2, 0.001)
+        };
+        let wins = Wins::from(vec![Win::FIRST; 10]);
+
+        assert!(matches!(rule.check(&wins), Convergence::Exhausted(_)));
+    }
+
+    #[test]
+    fn check__invalid_confidence() {
+        let rule = StoppingRule {
+            confidence: 95.0,
+            max_iterations: 1_000,
+            ..StoppingRule::new(2, 1.0)
+        };
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 500);
+
+        assert!(matches!(rule.check(&wins), Convergence::Running(_)));
+        wins.add_x(Win::FIRST, 500);
+        assert!(matches!(rule.check(&wins), Convergence::Exhausted(_)));
+    }
+
+    #[test]
+    fn run__stops_at_max_iterations() {
+        let rule = StoppingRule {
+            max_iterations: 250,
+            ..StoppingRule::new(3, 0.01)
+        };
+        let mut wins = Wins::default();
+        let mut flip = false;
+
+        let convergence = rule.run(&mut wins, 100, || {
+            flip = !flip;
+            if flip {
+                Win::FIRST
+            } else {
+                Win::SECOND | Win::THIRD
+            }
+        });
+
+        assert!(matches!(convergence, Convergence::Exhausted(_)));
+        assert_eq!(250, wins.len());
+    }
+
+    #[test]
+    fn convergence__margin() {
+        assert_eq!(1.5, Convergence::Running(1.5).margin());
+        assert_eq!(0.5, Convergence::Converged(0.5).margin());
+        assert!(Convergence::Exhausted(2.0).is_done());
+    }
+}
@@ -0,0 +1,712 @@
+//! CSV import and export, for everybody who'd rather be in a spreadsheet.
+//!
+//! There are three kinds of files:
+//!
+//! * **Results** - One row per player, with their wins, ties, totals, percentages and equity.
+//!   [`WinResults`] and [`HeadsUp`] both read and write this format. The `split_ways` column
+//!   holds the number of ties for each size of split (`2:32116 3:7`), so that equity survives
+//!   the round trip. The exact co-winner sets don't make it into the file. Players with a
+//!   label have it in the last column, in quotes if it needs them. Cases nobody won get a row
+//!   of their own at the end, with `none` for the player and the count under `wins`.
+//! * **Tallies** - One row per distinct flag pattern in a [`Wins`], with the players in it
+//!   numbered from one, and the number of times it happened. This is the lossless format.
+//!   Outcomes nobody won are a `none` pattern with no players.
+//! * **Head to head** - One row for every ordered pair of players in a [`HeadToHead`], numbered
+//!   from one. This one is export only.
+//!
+//! ```
+//! use wincounter::heads_up::HeadsUp;
+//!
+//! let the_hand = HeadsUp::new(1_365_284, 314_904, 32_116);
+//! let csv = the_hand.to_csv();
+//!
+//! assert_eq!(
+//!     "player,wins,ties,total,cases,win_percentage,tie_percentage,total_percentage,equity_percentage,split_ways,label\n\
+//!      1,1365284,32116,1397400,1712304,79.7337,1.8756,81.6093,80.6715,2:32116,\n\
+//!      2,314904,32116,347020,1712304,18.3907,1.8756,20.2663,19.3285,2:32116,\n",
+//!     csv
+//! );
+//! assert_eq!(the_hand, HeadsUp::from_csv(&csv).unwrap());
+//! ```
+use crate::flag::Flag;
+use crate::head_to_head::HeadToHead;
+use crate::heads_up::HeadsUp;
+use crate::results::WinResults;
+use crate::util::Util;
+use crate::wins::Wins;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+pub const RESULTS_HEADER: &str = "player,wins,ties,total,cases,win_percentage,tie_percentage,total_percentage,equity_percentage,split_ways,label";
+pub const TALLY_HEADER: &str = "pattern,players,count";
+pub const HEAD_TO_HEAD_HEADER: &str =
+    "player,opponent,wins,losses,ties,neither,win_percentage,loss_percentage,tie_percentage";
+
+/// Stands in for the player in a results row, or the pattern in a tally row, when nobody won.
+pub const NONE: &str = "none";
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// Something in the file didn't make sense. `line` is numbered from one.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl CsvError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        CsvError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "{e}"),
+            CsvError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(e) => Some(e),
+            CsvError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(e: io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+impl WinResults {
+    /// One row per player in the results CSV format. See the [module docs](crate::csv).
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![RESULTS_HEADER.to_string()];
+        for i in 0..self.v.len() {
+            let (wins, ties) = self.wins_and_ties(i);
+            let (win_percentage, tie_percentage) = self.wins_and_ties_percentages(i);
+            let split_ways: Vec<String> = self
+                .ties_by_ways(i)
+                .iter()
+                .map(|(k, x)| format!("{k}:{x}"))
+                .collect();
+            lines.push(format!(
+                "{},{wins},{ties},{},{},{win_percentage:.4},{tie_percentage:.4},{:.4},{:.4},{},{}",
+                i + 1,
+                wins + ties,
+                self.case_count,
+                self.wins_total_percentage(i),
+                self.equity_percentage(i),
+                split_ways.join(" "),
+                escape(self.labels.get(i).map_or("", String::as_str))
+            ));
+        }
+        if self.no_winner > 0 {
+            let percentage = self.no_winner_percentage();
+            lines.push(format!(
+                "{NONE},{0},0,{0},{1},{percentage:.4},0.0000,{percentage:.4},0.0000,,",
+                self.no_winner, self.case_count
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Rebuilds `WinResults` from the results CSV format. The percentage and total columns are
+    /// for people, and are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CsvError::Parse` if the header is missing, a number can't be read, the players
+    /// are out of order, or the rows don't agree on the number of cases.
+    pub fn from_csv(s: &str) -> Result<WinResults, CsvError> {
+        WinResults::parse_csv(s.lines().map(|line| Ok(line.to_string())))
+    }
+
+    /// Reads the results CSV format from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if the file can't be read, or `CsvError::Parse` as with
+    /// [`from_csv()`](WinResults::from_csv).
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<WinResults, CsvError> {
+        WinResults::parse_csv(Util::read_lines(path)?)
+    }
+
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if the file can't be written.
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), CsvError> {
+        Ok(std::fs::write(path, self.to_csv())?)
+    }
+
+    fn parse_csv<I: Iterator<Item = io::Result<String>>>(lines: I) -> Result<Self, CsvError> {
+        let mut results = WinResults::default();
+        let mut cases: Option<usize> = None;
+        for (n, fields) in records(lines, RESULTS_HEADER, 5)? {
+            if fields.len() < 5 {
+                return Err(CsvError::parse(n, "expected at least 5 columns"));
+            }
+            let case_count = number(n, "cases", &fields[4])?;
+            if cases.is_some_and(|c| c != case_count) {
+                return Err(CsvError::parse(n, "rows disagree on the number of cases"));
+            }
+            cases = Some(case_count);
+            if fields[0] == NONE {
+                results.no_winner += number::<usize>(n, "wins", &fields[1])?;
+                continue;
+            }
+            let player: usize = number(n, "player", &fields[0])?;
+            if player != results.v.len() + 1 {
+                return Err(CsvError::parse(
+                    n,
+                    format!(
+                        "expected player {}, found player {player}",
+                        results.v.len() + 1
+                    ),
+                ));
+            }
+            let wins = number(n, "wins", &fields[1])?;
+            let ties = number(n, "ties", &fields[2])?;
+            let mut split_ways = BTreeMap::new();
+            if let Some(column) = fields.get(9) {
+                for pair in column.split_whitespace() {
+                    let (k, x) = pair
+                        .split_once(':')
+                        .ok_or_else(|| CsvError::parse(n, format!("bad split_ways `{pair}`")))?;
+                    split_ways.insert(number(n, "split_ways", k)?, number(n, "split_ways", x)?);
+                }
+            }
+            results.v.push((wins, ties));
+            results.split_ways.push(split_ways);
+            results.co_winners.push(BTreeMap::new());
+            results
+                .labels
+                .push(fields.get(10).cloned().unwrap_or_default());
+        }
+        if results.labels.iter().all(String::is_empty) {
+            results.labels.clear();
+        }
+        results.player_count = results.v.len();
+        results.case_count = cases.unwrap_or_default();
+        Ok(results)
+    }
+}
+
+impl HeadsUp {
+    /// Two rows, in the same results CSV format as `WinResults`.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        WinResults::from(self).to_csv()
+    }
+
+    /// Reads the first two players from the results CSV format.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`WinResults::from_csv()`].
+    pub fn from_csv(s: &str) -> Result<HeadsUp, CsvError> {
+        Ok(HeadsUp::from(&WinResults::from_csv(s)?))
+    }
+
+    /// # Errors
+    ///
+    /// Same as [`WinResults::read_csv()`].
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<HeadsUp, CsvError> {
+        Ok(HeadsUp::from(&WinResults::read_csv(path)?))
+    }
+
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if the file can't be written.
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), CsvError> {
+        Ok(std::fs::write(path, self.to_csv())?)
+    }
+}
+
+impl HeadToHead {
+    /// One row per ordered pair of players, `wins` being the row player's.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![HEAD_TO_HEAD_HEADER.to_string()];
+        for i in 0..self.player_count {
+            for j in 0..self.player_count {
+                if let Some(hup) = self.get(i, j) {
+                    lines.push(format!(
+                        "{},{},{},{},{},{},{:.4},{:.4},{:.4}",
+                        i + 1,
+                        j + 1,
+                        hup.first_wins,
+                        hup.second_wins,
+                        hup.ties,
+                        hup.no_winner,
+                        hup.percentage_first(),
+                        hup.percentage_second(),
+                        hup.percentage_ties()
+                    ));
+                }
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+impl<F: Flag> Wins<F> {
+    /// One row per distinct flag pattern, in the tally CSV format.
+    ///
+    /// ```
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 80);
+    /// wins.add_x(Win::FIRST | Win::THIRD, 5);
+    ///
+    /// assert_eq!("pattern,players,count\n1,1,80\n5,1 3,5\n", wins.to_csv());
+    /// ```
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![TALLY_HEADER.to_string()];
+        for (count, x) in self.iter() {
+            let players: Vec<String> = count.indexes().map(|i| (i + 1).to_string()).collect();
+            lines.push(format!("{},{},{x}", count.to_u128(), players.join(" ")));
+        }
+        if self.no_winner() > 0 {
+            lines.push(format!("{NONE},,{}", self.no_winner()));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Rebuilds a `Wins` from the tally CSV format. The `players` column is for people. The
+    /// `pattern` column is what's used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CsvError::Parse` if the header is missing, a number can't be read, or a
+    /// pattern doesn't fit in the flag width.
+    pub fn from_csv(s: &str) -> Result<Wins<F>, CsvError> {
+        Wins::parse_csv(s.lines().map(|line| Ok(line.to_string())))
+    }
+
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if the file can't be read, or `CsvError::Parse` as with
+    /// [`from_csv()`](Wins::from_csv).
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<Wins<F>, CsvError> {
+        Wins::parse_csv(Util::read_lines(path)?)
+    }
+
+    /// # Errors
+    ///
+    /// Returns `CsvError::Io` if the file can't be written.
+    ///
+    /// Note: This function is not available when targeting WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), CsvError> {
+        Ok(std::fs::write(path, self.to_csv())?)
+    }
+
+    fn parse_csv<I: Iterator<Item = io::Result<String>>>(lines: I) -> Result<Self, CsvError> {
+        let mut wins = Wins::new();
+        for (n, fields) in records(lines, TALLY_HEADER, 3)? {
+            if fields.len() < 3 {
+                return Err(CsvError::parse(n, "expected 3 columns"));
+            }
+            if fields[0] == NONE {
+                wins.add_no_winner_x(number(n, "count", &fields[2])?);
+                continue;
+            }
+            let pattern: u128 = number(n, "pattern", &fields[0])?;
+            let count = F::from_u128(pattern).ok_or_else(|| {
+                CsvError::parse(
+                    n,
+                    format!("pattern {pattern} doesn't fit in {} bits", F::CAPACITY),
+                )
+            })?;
+            wins.add_x(count, number(n, "count", &fields[2])?);
+        }
+        Ok(wins)
+    }
+}
+
+/// Checks the header, then hands back the rest of the non-blank records split into fields, along
+/// with the line numbers they start on. A quoted field can run over more than one line.
+///
+/// The header has to match `header` column for column, although it can stop short after the
+/// first `columns` of them, for files written before the later columns were added.
+fn records<I: Iterator<Item = io::Result<String>>>(
+    lines: I,
+    header: &str,
+    columns: usize,
+) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let mut records = Vec::new();
+    let mut seen_header = false;
+    let mut open: Option<(usize, String)> = None;
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        let (n, record) = match open.take() {
+            Some((n, record)) => (n, format!("{record}\n{line}")),
+            None if line.trim().is_empty() => continue,
+            None => (i + 1, line.to_string()),
+        };
+        if record.matches('"').count() % 2 == 1 {
+            open = Some((n, record));
+            continue;
+        }
+        let fields = split_fields(&record);
+        if seen_header {
+            records.push((n, fields));
+        } else {
+            let expected = Util::str_splitter(header, ",");
+            if fields.len() < columns.min(expected.len())
+                || fields.len() > expected.len()
+                || fields.iter().zip(&expected).any(|(a, b)| a != b)
+            {
+                return Err(CsvError::parse(n, format!("expected header `{header}`")));
+            }
+            seen_header = true;
+        }
+    }
+    if let Some((n, _)) = open {
+        return Err(CsvError::parse(n, "a quote is never closed"));
+    }
+    if seen_header {
+        Ok(records)
+    } else {
+        Err(CsvError::parse(1, format!("missing header `{header}`")))
+    }
+}
+
+/// Splits on commas, except inside double quotes, where `""` is a literal quote. Unquoted fields
+/// are trimmed; quoted ones are left exactly as they are, line breaks included.
+fn split_fields(line: &str) -> Vec<String> {
+    if !line.contains('"') {
+        return Util::str_splitter(line, ",")
+            .into_iter()
+            .map(|field| field.trim().to_string())
+            .collect();
+    }
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+                in_quotes = true;
+            },
+            ',' if !in_quotes => {
+                fields.push(finish_field(&mut field, quoted));
+                quoted = false;
+            },
+            _ if quoted && !in_quotes => {},
+            _ => field.push(c),
+        }
+    }
+    fields.push(finish_field(&mut field, quoted));
+    fields
+}
+
+fn finish_field(field: &mut String, quoted: bool) -> String {
+    let done = std::mem::take(field);
+    if quoted {
+        done
+    } else {
+        done.trim().to_string()
+    }
+}
+
+/// Quotes a field if it has anything in it that would throw off [`split_fields()`].
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn number<T: std::str::FromStr>(line: usize, column: &str, field: &str) -> Result<T, CsvError> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| CsvError::parse(line, format!("bad {column} `{field}`")))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp, clippy::unwrap_used)]
+mod util__wincounter__csv__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn three_way() -> Wins {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 50);
+        wins.add_x(Win::SECOND, 20);
+        wins.add_x(Win::FIRST | Win::SECOND, 20);
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 10);
+        wins
+    }
+
+    #[test]
+    fn win_results__to_csv() {
+        let results = WinResults::from_wins(&three_way(), 3);
+
+        assert_eq!(
+            format!(
+                "{RESULTS_HEADER}\n\
+                 1,50,30,80,100,50.0000,30.0000,80.0000,63.3333,2:20 3:10,\n\
+                 2,20,30,50,100,20.0000,30.0000,50.0000,33.3333,2:20 3:10,\n\
+                 3,0,10,10,100,0.0000,10.0000,10.0000,3.3333,3:10,\n"
+            ),
+            results.to_csv()
+        );
+    }
+
+    #[test]
+    fn win_results__round_trip() {
+        let results = WinResults::from_wins(&three_way(), 3);
+
+        let back = WinResults::from_csv(&results.to_csv()).unwrap();
+
+        assert_eq!(results.case_count, back.case_count);
+        assert_eq!(results.player_count, back.player_count);
+        assert_eq!(results.v, back.v);
+        assert_eq!(results.split_ways, back.split_ways);
+        for i in 0..3 {
+            assert_eq!(results.equity(i), back.equity(i));
+        }
+    }
+
+    #[test]
+    fn win_results__labels() {
+        let results = WinResults::from_wins(&three_way(), 3).with_labels([
+            "Negreanu",
+            "",
+            "Ivey, \"The Tiger\"",
+        ]);
+
+        let csv = results.to_csv();
+        let back = WinResults::from_csv(&csv).unwrap();
+
+        assert!(csv.contains(",3:10,\"Ivey, \"\"The Tiger\"\"\"\n"));
+        assert_eq!(results.labels, back.labels);
+        assert!(
+            WinResults::from_csv(&WinResults::from_wins(&three_way(), 3).to_csv())
+                .unwrap()
+                .labels
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn win_results__labels__line_breaks() {
+        let results = WinResults::from_wins(&three_way(), 3).with_labels(["Phil\nIvey", "", "\n"]);
+
+        let back = WinResults::from_csv(&results.to_csv()).unwrap();
+
+        assert_eq!(results.labels, back.labels);
+        assert_eq!(results.v, back.v);
+    }
+
+    #[test]
+    fn win_results__from_csv__before_labels() {
+        let csv = "player,wins,ties,total,cases,win_percentage,tie_percentage,total_percentage,equity_percentage,split_ways\n\
+                   1,3,1,4,5,,,,,2:1\n";
+
+        assert_eq!(vec![(3, 1)], WinResults::from_csv(csv).unwrap().v);
+    }
+
+    #[test]
+    fn split_fields() {
+        assert_eq!(vec!["a", "b", ""], super::split_fields(" a , b,"));
+        assert_eq!(
+            vec!["a", " b, \"c\" ", "d"],
+            super::split_fields("a, \" b, \"\"c\"\" \" ,d")
+        );
+        assert_eq!("\" padded\"", escape(" padded"));
+        assert_eq!("plain", escape("plain"));
+    }
+
+    #[test]
+    fn win_results__from_csv__windows_line_endings() {
+        let csv = format!("{RESULTS_HEADER}\r\n1,3,1,4,5,,,,,2:1\r\n2,1,1,2,5\r\n");
+
+        let results = WinResults::from_csv(&csv).unwrap();
+
+        assert_eq!(vec![(3, 1), (1, 1)], results.v);
+        assert_eq!(5, results.case_count);
+    }
+
+    #[test]
+    fn win_results__from_csv__errors() {
+        assert!(matches!(
+            WinResults::from_csv(""),
+            Err(CsvError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            WinResults::from_csv("pattern,players,count\n"),
+            Err(CsvError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            WinResults::from_csv("\nplayer,opponent,wins\n"),
+            Err(CsvError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            WinResults::from_csv(&format!("{RESULTS_HEADER},extra\n")),
+            Err(CsvError::Parse { line: 1, .. })
+        ));
+        let unclosed = format!("{RESULTS_HEADER}\n1,1,0,1,1,,,,,,\"Ivey\n");
+        assert_eq!(
+            "line 2: a quote is never closed",
+            WinResults::from_csv(&unclosed).unwrap_err().to_string()
+        );
+        let out_of_order = format!("{RESULTS_HEADER}\n2,1,0,1,1\n");
+        assert_eq!(
+            "line 2: expected player 1, found player 2",
+            WinResults::from_csv(&out_of_order).unwrap_err().to_string()
+        );
+        let disagree = format!("{RESULTS_HEADER}\n1,1,0,1,2\n2,1,0,1,3\n");
+        assert!(WinResults::from_csv(&disagree).is_err());
+        let bad_number = format!("{RESULTS_HEADER}\n1,x,0,1,2\n");
+        assert_eq!(
+            "line 2: bad wins `x`",
+            WinResults::from_csv(&bad_number).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn heads_up__round_trip() {
+        let hup = HeadsUp::new(40, 40, 20);
+
+        assert_eq!(hup, HeadsUp::from_csv(&hup.to_csv()).unwrap());
+    }
+
+    #[test]
+    fn no_winner__round_trip() {
+        let mut wins = three_way();
+        wins.add_no_winner_x(25);
+        let results = WinResults::from_wins(&wins, 3);
+        let hup = HeadsUp::new(40, 30, 20).with_no_winner(10);
+
+        let back = WinResults::from_csv(&results.to_csv()).unwrap();
+
+        assert!(wins.to_csv().ends_with("\nnone,,25\n"));
+        assert_eq!(wins, Wins::from_csv(&wins.to_csv()).unwrap());
+        assert!(results
+            .to_csv()
+            .ends_with("\nnone,25,0,25,125,20.0000,0.0000,20.0000,0.0000,,\n"));
+        assert_eq!(25, back.no_winner);
+        assert_eq!(125, back.case_count);
+        assert_eq!(3, back.player_count);
+        assert_eq!(
+            7,
+            WinResults::from_csv(&format!("{RESULTS_HEADER}\nnone,7,0,7,7\n"))
+                .unwrap()
+                .case_count
+        );
+        assert!(
+            WinResults::from_csv(&format!("{RESULTS_HEADER}\n1,1,0,1,2\nnone,1,0,1,3\n")).is_err()
+        );
+        assert_eq!(
+            5,
+            WinResults::from_csv(&format!("{RESULTS_HEADER}\nnone,2,0,2,5\nnone,3,0,3,5\n"))
+                .unwrap()
+                .no_winner
+        );
+        assert_eq!(hup, HeadsUp::from_csv(&hup.to_csv()).unwrap());
+    }
+
+    #[test]
+    fn head_to_head__to_csv() {
+        assert_eq!(
+            "player,opponent,wins,losses,ties,neither,win_percentage,loss_percentage,tie_percentage\n\
+             1,2,50,20,30,0,50.0000,20.0000,30.0000\n\
+             2,1,20,50,30,0,20.0000,50.0000,30.0000\n",
+            three_way().head_to_head(2).to_csv()
+        );
+    }
+
+    #[test]
+    fn wins__round_trip() {
+        let wins = three_way();
+
+        let csv = wins.to_csv();
+        let back: Wins = Wins::from_csv(&csv).unwrap();
+
+        assert_eq!(
+            "pattern,players,count\n1,1,50\n2,2,20\n3,1 2,20\n7,1 2 3,10\n",
+            csv
+        );
+        assert_eq!(wins, back);
+    }
+
+    #[test]
+    fn wins__from_csv__too_wide() {
+        let csv = "pattern,players,count\n256,9,1\n";
+
+        assert!(Wins::<u8>::from_csv(csv).is_err());
+        assert_eq!(1, Wins::<u16>::from_csv(csv).unwrap().count(256));
+    }
+
+    #[test]
+    fn files() {
+        let dir = std::env::temp_dir();
+        let tally = dir.join("wincounter__csv__tests__tally.csv");
+        let results = dir.join("wincounter__csv__tests__results.csv");
+        let wins = three_way();
+
+        wins.write_csv(&tally).unwrap();
+        wins.results(3).write_csv(&results).unwrap();
+
+        assert_eq!(wins, Wins::read_csv(&tally).unwrap());
+        assert_eq!(wins.results(3).v, WinResults::read_csv(&results).unwrap().v);
+        assert!(matches!(
+            WinResults::read_csv(dir.join("wincounter__csv__tests__missing.csv")),
+            Err(CsvError::Io(_))
+        ));
+
+        std::fs::remove_file(tally).unwrap();
+        std::fs::remove_file(results).unwrap();
+    }
+}
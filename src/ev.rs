@@ -0,0 +1,591 @@
+//! Turning win percentages into chips.
+//!
+//! Percentages are nice, but nobody ever put 79.73% in the pot. What a decision actually turns
+//! on is how many chips a player gets back, on average, for the chips they put in. For that we
+//! need the [`Stakes`]: how big the pot is, how much of it each player put in, and how a tie
+//! gets split up. Feed those and a `WinResults` (or a `HeadsUp`) into
+//! [`WinResults::expected_values()`] and you get an [`ExpectedValue`] for every player.
+//!
+//! ```
+//! use wincounter::ev::Stakes;
+//! use wincounter::heads_up::HeadsUp;
+//!
+//! // The Hand, with each of them all in for 50.
+//! let the_hand = HeadsUp::new(1_365_284, 314_904, 32_116);
+//! let stakes = Stakes::new(100.0, vec![50.0, 50.0]);
+//!
+//! let ev = the_hand.expected_values(&stakes).unwrap();
+//!
+//! assert_eq!(80.67, (ev[0].share * 100.0).round() / 100.0);
+//! assert_eq!(30.67, (ev[0].profit * 100.0).round() / 100.0);
+//! assert_eq!(-30.67, (ev[1].profit * 100.0).round() / 100.0);
+//! assert_eq!(0.5, ev[1].break_even);
+//! ```
+//!
+//! For the other side of it, whether a call is worth making, there's [`PotOdds`].
+//!
+//! ```
+//! use wincounter::ev::PotOdds;
+//!
+//! // 50 to call into a pot of 100 needs a third of the pot after calling.
+//! let odds = PotOdds::new(100.0, 50.0);
+//!
+//! assert_eq!(1.0 / 3.0, odds.break_even());
+//! assert!(odds.is_profitable(0.4));
+//! assert!(!odds.is_profitable(0.3));
+//! ```
+//!
+//! Amounts are `f64`, in whatever units you like. Equities here are fractions of the pot, from
+//! `0.0` to `1.0`, not percentages like the rest of the crate, since they get multiplied straight
+//! into chip counts.
+use crate::heads_up::HeadsUp;
+use crate::results::WinResults;
+use std::fmt::{Display, Formatter};
+
+/// How a pot is shared between players who tie.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TieSplit {
+    /// Everyone who tied gets the same share. This is what `WinResults::equity()` assumes.
+    #[default]
+    Even,
+    /// Everyone who tied gets a share in proportion to what they put in. If none of them put
+    /// anything in, it's split evenly.
+    ByContribution,
+}
+
+/// What's riding on the outcome.
+///
+/// `pot` is everything that goes to the winner, contributions included, so it's the
+/// contributions plus any dead money. `contributions` is what each player put in, by index.
+/// When nobody wins, everybody gets their contribution back and the dead money is gone.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stakes {
+    pub pot: f64,
+    pub contributions: Vec<f64>,
+    pub tie_split: TieSplit,
+}
+
+impl Stakes {
+    #[must_use]
+    pub fn new(pot: f64, contributions: Vec<f64>) -> Self {
+        Self {
+            pot,
+            contributions,
+            tie_split: TieSplit::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_tie_split(mut self, tie_split: TieSplit) -> Self {
+        self.tie_split = tie_split;
+        self
+    }
+
+    /// The total everybody put in.
+    #[must_use]
+    pub fn contributed(&self) -> f64 {
+        self.contributions.iter().sum()
+    }
+
+    /// What the player put in, or nothing if they aren't in the list.
+    #[must_use]
+    pub fn contribution(&self, player_index: usize) -> f64 {
+        self.contributions
+            .get(player_index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Checks the stakes against the number of players.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StakesError` if there isn't exactly one contribution per player, if any amount
+    /// is negative, infinite or not a number, or if the contributions add up to more than the
+    /// pot.
+    pub fn validate(&self, player_count: usize) -> Result<(), StakesError> {
+        if self.contributions.len() != player_count {
+            return Err(StakesError::PlayerCount {
+                player_count,
+                contributions: self.contributions.len(),
+            });
+        }
+        let mut amounts = std::iter::once(&self.pot).chain(&self.contributions);
+        if amounts.any(|amount| !amount.is_finite() || *amount < 0.0) {
+            return Err(StakesError::Negative);
+        }
+        if self.contributed() > self.pot {
+            return Err(StakesError::OverPot {
+                pot: self.pot,
+                contributed: self.contributed(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The player's share of a pot they won along with `others`.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn tie_share(&self, player_index: usize, others: &[usize]) -> f64 {
+        let even = self.pot / (others.len() + 1) as f64;
+        match self.tie_split {
+            TieSplit::Even => even,
+            TieSplit::ByContribution => {
+                let total: f64 = self.contribution(player_index)
+                    + others.iter().map(|j| self.contribution(*j)).sum::<f64>();
+                if total > 0.0 {
+                    self.pot * self.contribution(player_index) / total
+                } else {
+                    even
+                }
+            },
+        }
+    }
+}
+
+/// Something wrong with the `Stakes`, or with the results they're being applied to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StakesError {
+    PlayerCount {
+        player_count: usize,
+        contributions: usize,
+    },
+    Negative,
+    OverPot {
+        pot: f64,
+        contributed: f64,
+    },
+    /// The player tied, but the results don't say who with, and the split needs to know. See
+    /// [`WinResults::expected_values()`].
+    UnknownTies {
+        player_index: usize,
+    },
+}
+
+impl Display for StakesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StakesError::PlayerCount {
+                player_count,
+                contributions,
+            } => write!(
+                f,
+                "{contributions} contributions for {player_count} players"
+            ),
+            StakesError::Negative => write!(
+                f,
+                "the pot and contributions have to be finite and not negative"
+            ),
+            StakesError::OverPot { pot, contributed } => {
+                write!(
+                    f,
+                    "contributions of {contributed} are more than the pot of {pot}"
+                )
+            },
+            StakesError::UnknownTies { player_index } => write!(
+                f,
+                "there's no record of who player #{} tied with",
+                player_index + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StakesError {}
+
+/// One player's expected result.
+///
+/// `share` is the chips they can expect back, `contribution` what they put in, and `profit` the
+/// difference. `break_even` is the fraction of the pot they need to get back to not lose
+/// anything, which is their contribution over the pot, and `equity` is the fraction they
+/// actually get.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExpectedValue {
+    pub share: f64,
+    pub contribution: f64,
+    pub profit: f64,
+    pub equity: f64,
+    pub break_even: f64,
+}
+
+impl ExpectedValue {
+    #[must_use]
+    pub fn is_profitable(&self) -> bool {
+        self.profit > 0.0
+    }
+}
+
+impl Display for ExpectedValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:+.2} ({:.2} back for {:.2}, {:.2}% of the pot, {:.2}% to break even)",
+            self.profit,
+            self.share,
+            self.contribution,
+            self.equity * 100.0,
+            self.break_even * 100.0
+        )
+    }
+}
+
+impl WinResults {
+    /// Every player's expected share of the pot, and profit, over all the cases.
+    ///
+    /// Ties are split using `co_winners`. Results without them, like the ones read back from
+    /// CSV, can still be split evenly from `split_ways`, but not by contribution.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StakesError` if the stakes don't pass [`Stakes::validate()`], or
+    /// `StakesError::UnknownTies` if a player's ties can't be split the way the stakes ask.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn expected_values(&self, stakes: &Stakes) -> Result<Vec<ExpectedValue>, StakesError> {
+        stakes.validate(self.player_count)?;
+        (0..self.player_count)
+            .map(|i| {
+                let contribution = stakes.contribution(i);
+                let share = if self.case_count == 0 {
+                    contribution
+                } else {
+                    let (wins, _) = self.wins_and_ties(i);
+                    let ties = self
+                        .tie_chips(i, stakes)
+                        .ok_or(StakesError::UnknownTies { player_index: i })?;
+                    let refunds = contribution * self.no_winner as f64;
+                    (wins as f64 * stakes.pot + ties + refunds) / self.case_count as f64
+                };
+                Ok(ExpectedValue {
+                    share,
+                    contribution,
+                    profit: share - contribution,
+                    equity: fraction(share, stakes.pot),
+                    break_even: fraction(contribution, stakes.pot),
+                })
+            })
+            .collect()
+    }
+
+    /// How a call by the player stacks up against the pot odds, using their
+    /// [equity](WinResults::equity) as the chance of taking the pot.
+    #[must_use]
+    pub fn pot_odds(&self, player_index: usize, odds: &PotOdds) -> PotOddsComparison {
+        odds.compare(f64::from(self.equity_percentage(player_index)) / 100.0)
+    }
+
+    /// The chips the player took home from all their ties put together, or `None` if there's
+    /// nothing to split them with.
+    #[allow(clippy::cast_precision_loss)]
+    fn tie_chips(&self, player_index: usize, stakes: &Stakes) -> Option<f64> {
+        let (_, ties) = self.wins_and_ties(player_index);
+        if ties == 0 {
+            return Some(0.0);
+        }
+        if let Some(co_winners) = self.complete_co_winners(player_index) {
+            return Some(
+                co_winners
+                    .iter()
+                    .map(|(others, x)| stakes.tie_share(player_index, others) * *x as f64)
+                    .sum(),
+            );
+        }
+        let split_ways = self
+            .split_ways
+            .get(player_index)
+            .filter(|split_ways| split_ways.values().sum::<usize>() == ties)?;
+        (stakes.tie_split == TieSplit::Even).then(|| {
+            split_ways
+                .iter()
+                .map(|(k, x)| stakes.pot / *k as f64 * *x as f64)
+                .sum()
+        })
+    }
+}
+
+impl HeadsUp {
+    /// `WinResults::expected_values()` for the two players.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StakesError` if the stakes don't pass [`Stakes::validate()`] for two players.
+    pub fn expected_values(&self, stakes: &Stakes) -> Result<Vec<ExpectedValue>, StakesError> {
+        WinResults::from(self).expected_values(stakes)
+    }
+}
+
+fn fraction(amount: f64, pot: f64) -> f64 {
+    if pot > 0.0 {
+        amount / pot
+    } else {
+        0.0
+    }
+}
+
+/// A call of `to_call` into a pot that's already `pot`, counting everything in it, bets the
+/// caller is facing included.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PotOdds {
+    pub pot: f64,
+    pub to_call: f64,
+}
+
+impl PotOdds {
+    #[must_use]
+    pub fn new(pot: f64, to_call: f64) -> Self {
+        Self { pot, to_call }
+    }
+
+    /// The equity needed for calling to break even, which is the call over the pot after
+    /// calling.
+    #[must_use]
+    pub fn break_even(&self) -> f64 {
+        fraction(self.to_call, self.pot + self.to_call)
+    }
+
+    /// The ratio of the pot to the call, like the `2` in "2 to 1".
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        if self.to_call > 0.0 {
+            self.pot / self.to_call
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// The expected profit from calling with `equity`, compared to folding.
+    #[must_use]
+    pub fn ev(&self, equity: f64) -> f64 {
+        equity * (self.pot + self.to_call) - self.to_call
+    }
+
+    #[must_use]
+    pub fn is_profitable(&self, equity: f64) -> bool {
+        self.ev(equity) > 0.0
+    }
+
+    #[must_use]
+    pub fn compare(&self, equity: f64) -> PotOddsComparison {
+        PotOddsComparison {
+            equity,
+            break_even: self.break_even(),
+            ev: self.ev(equity),
+        }
+    }
+}
+
+/// A player's equity next to the equity the pot odds ask for.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PotOddsComparison {
+    pub equity: f64,
+    pub break_even: f64,
+    pub ev: f64,
+}
+
+impl PotOddsComparison {
+    /// How much more equity than they need the player has. Negative if it's less.
+    #[must_use]
+    pub fn margin(&self) -> f64 {
+        self.equity - self.break_even
+    }
+
+    #[must_use]
+    pub fn is_profitable(&self) -> bool {
+        self.ev > 0.0
+    }
+}
+
+impl Display for PotOddsComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2}% equity against {:.2}% needed, {:+.2} to call",
+            self.equity * 100.0,
+            self.break_even * 100.0,
+            self.ev
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp, clippy::unwrap_used)]
+mod util__wincounter__ev__tests {
+    use super::*;
+    use crate::win::Win;
+    use crate::wins::Wins;
+    use std::collections::BTreeMap;
+
+    fn round(x: f64) -> f64 {
+        (x * 100.0).round() / 100.0
+    }
+
+    fn three_way() -> WinResults {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 5);
+        wins.add_x(Win::SECOND, 2);
+        wins.add_x(Win::FIRST | Win::THIRD, 2);
+        wins.add_no_winner();
+        wins.results(3)
+    }
+
+    #[test]
+    fn stakes__validate() {
+        let stakes = Stakes::new(100.0, vec![50.0, 40.0]);
+
+        assert!(stakes.validate(2).is_ok());
+        assert_eq!(
+            Err(StakesError::PlayerCount {
+                player_count: 3,
+                contributions: 2
+            }),
+            stakes.validate(3)
+        );
+        assert_eq!(
+            Err(StakesError::Negative),
+            Stakes::new(100.0, vec![-1.0]).validate(1)
+        );
+        assert_eq!(
+            Err(StakesError::Negative),
+            Stakes::new(f64::NAN, vec![1.0]).validate(1)
+        );
+        assert_eq!(
+            Err(StakesError::Negative),
+            Stakes::new(f64::INFINITY, vec![1.0]).validate(1)
+        );
+        assert_eq!(
+            "contributions of 120 are more than the pot of 100",
+            Stakes::new(100.0, vec![60.0, 60.0])
+                .validate(2)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn expected_values() {
+        let stakes = Stakes::new(90.0, vec![30.0, 30.0, 20.0]);
+
+        let ev = three_way().expected_values(&stakes).unwrap();
+
+        // 5 wins of 90, 2 chops of 45, and 30 back once.
+        assert_eq!(57.0, round(ev[0].share));
+        assert_eq!(27.0, round(ev[0].profit));
+        assert_eq!(21.0, round(ev[1].share));
+        assert_eq!(-9.0, round(ev[1].profit));
+        assert_eq!(11.0, round(ev[2].share));
+        assert_eq!(-9.0, round(ev[2].profit));
+        assert_eq!(1.0 / 3.0, ev[0].break_even);
+        assert!(ev[0].is_profitable());
+        assert!(!ev[2].is_profitable());
+    }
+
+    #[test]
+    fn expected_values__by_contribution() {
+        let stakes =
+            Stakes::new(90.0, vec![30.0, 30.0, 20.0]).with_tie_split(TieSplit::ByContribution);
+
+        let ev = three_way().expected_values(&stakes).unwrap();
+
+        // The chops are now 54 and 36.
+        assert_eq!(58.8, round(ev[0].share));
+        assert_eq!(9.2, round(ev[2].share));
+    }
+
+    #[test]
+    fn expected_values__from_csv() {
+        let results = three_way();
+        let stakes = Stakes::new(90.0, vec![30.0, 30.0, 20.0]);
+
+        let back = WinResults::from_csv(&results.to_csv()).unwrap();
+
+        assert!(back.co_winners.iter().all(BTreeMap::is_empty));
+        assert_eq!(
+            results.expected_values(&stakes),
+            back.expected_values(&stakes)
+        );
+        assert_eq!(
+            Err(StakesError::UnknownTies { player_index: 0 }),
+            back.expected_values(&stakes.with_tie_split(TieSplit::ByContribution))
+        );
+    }
+
+    #[test]
+    fn expected_values__matches_equity() {
+        let results = three_way();
+        let stakes = Stakes::new(3.0, vec![1.0, 1.0, 1.0]);
+
+        let ev = results.expected_values(&stakes).unwrap();
+
+        for (i, ev) in ev.iter().enumerate() {
+            let refund = 0.1;
+            assert_eq!(
+                round(results.equity(i) * 3.0 / 10.0 + refund),
+                round(ev.share)
+            );
+        }
+    }
+
+    #[test]
+    fn expected_values__no_cases() {
+        let results = Wins::default().results(2);
+
+        let ev = results
+            .expected_values(&Stakes::new(10.0, vec![5.0, 5.0]))
+            .unwrap();
+
+        assert_eq!(0.0, ev[0].profit);
+        assert_eq!(0.0, ev[1].profit);
+    }
+
+    #[test]
+    fn expected_values__heads_up() {
+        let hup = HeadsUp::new(60, 30, 10);
+
+        let ev = hup
+            .expected_values(&Stakes::new(200.0, vec![100.0, 100.0]))
+            .unwrap();
+
+        assert_eq!(130.0, round(ev[0].share));
+        assert_eq!(-30.0, round(ev[1].profit));
+        assert_eq!(0.65, round(ev[0].equity));
+        assert!(hup.expected_values(&Stakes::default()).is_err());
+    }
+
+    #[test]
+    fn expected_value__display() {
+        let ev = HeadsUp::new(60, 30, 10)
+            .expected_values(&Stakes::new(200.0, vec![100.0, 100.0]))
+            .unwrap();
+
+        assert_eq!(
+            "+30.00 (130.00 back for 100.00, 65.00% of the pot, 50.00% to break even)",
+            ev[0].to_string()
+        );
+    }
+
+    #[test]
+    fn pot_odds() {
+        let odds = PotOdds::new(150.0, 50.0);
+
+        assert_eq!(0.25, odds.break_even());
+        assert_eq!(3.0, odds.ratio());
+        assert_eq!(0.0, odds.ev(0.25));
+        assert_eq!(10.0, round(odds.ev(0.3)));
+        assert!(odds.is_profitable(0.3));
+        assert!(!odds.is_profitable(0.25));
+        assert_eq!(f64::INFINITY, PotOdds::new(10.0, 0.0).ratio());
+    }
+
+    #[test]
+    fn pot_odds__compare() {
+        let results = WinResults::from(&HeadsUp::new(60, 30, 10));
+
+        let comparison = results.pot_odds(1, &PotOdds::new(150.0, 50.0));
+
+        assert_eq!(0.35, round(comparison.equity));
+        assert_eq!(0.1, round(comparison.margin()));
+        assert!(comparison.is_profitable());
+        assert_eq!(
+            "35.00% equity against 25.00% needed, +20.00 to call",
+            comparison.to_string()
+        );
+    }
+}
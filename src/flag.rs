@@ -0,0 +1,192 @@
+use std::fmt::{Binary, Debug, Display};
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+/// The bit width behind a player flag.
+///
+/// `PlayerFlag` has always been a `u16`, which caps a game at 16 players. That's plenty for a
+/// poker table, but not for a tournament table or a battle royale. `Flag` lets `Wins` work with
+/// any of the unsigned integer types, from `u8` (8 players) up to `u128` (128 players). The
+/// original `u16` is still the default everywhere.
+///
+/// ```
+/// use wincounter::flag::Flag;
+///
+/// assert_eq!(Some(0b0000_0100_u8), u8::from_index(2));
+/// assert_eq!(None, u8::from_index(8));
+/// assert_eq!(Some(1_u128 << 99), u128::from_index(99));
+/// assert_eq!(vec![0, 2, 5], 0b10_0101_u32.indexes().collect::<Vec<usize>>());
+/// ```
+pub trait Flag:
+    Copy
+    + Debug
+    + Default
+    + Display
+    + Binary
+    + Eq
+    + Ord
+    + Hash
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + Not<Output = Self>
+{
+    /// The maximum number of players that can be represented.
+    const CAPACITY: usize;
+
+    /// A flag with no players set.
+    const NONE: Self;
+
+    /// Returns the flag for a zero based player index, or `None` if the index doesn't fit.
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// The number of players set in the flag.
+    fn players(self) -> usize;
+
+    /// The index of the lowest player set in the flag, or `None` if no players are set.
+    fn lowest_index(self) -> Option<usize>;
+
+    /// Widens the flag into a `u128`, which can hold any of the supported widths.
+    fn to_u128(self) -> u128;
+
+    /// Narrows a `u128` into the flag, or `None` if bits are set beyond the capacity.
+    fn from_u128(value: u128) -> Option<Self>;
+
+    #[must_use]
+    fn is_none(self) -> bool {
+        self == Self::NONE
+    }
+
+    /// Returns true if the player at the zero based index is set in the flag.
+    #[must_use]
+    fn contains_index(self, index: usize) -> bool {
+        Self::from_index(index).is_some_and(|f| self & f != Self::NONE)
+    }
+
+    /// Iterates over the zero based indexes of the players set in the flag, lowest first.
+    fn indexes(self) -> Indexes<Self> {
+        Indexes(self)
+    }
+}
+
+/// Iterator over the player indexes set in a `Flag`. See `Flag::indexes()`.
+#[derive(Clone, Debug)]
+pub struct Indexes<F: Flag>(F);
+
+impl<F: Flag> Iterator for Indexes<F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.0.lowest_index()?;
+        if let Some(f) = F::from_index(index) {
+            self.0 &= !f;
+        }
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.players();
+        (n, Some(n))
+    }
+}
+
+impl<F: Flag> ExactSizeIterator for Indexes<F> {}
+
+macro_rules! impl_flag {
+    ($($t:ty),*) => {
+        $(
+            impl Flag for $t {
+                const CAPACITY: usize = <$t>::BITS as usize;
+                const NONE: Self = 0;
+
+                fn from_index(index: usize) -> Option<Self> {
+                    u32::try_from(index).ok().and_then(|i| (1 as $t).checked_shl(i))
+                }
+
+                fn players(self) -> usize {
+                    self.count_ones() as usize
+                }
+
+                fn lowest_index(self) -> Option<usize> {
+                    if self == 0 {
+                        None
+                    } else {
+                        Some(self.trailing_zeros() as usize)
+                    }
+                }
+
+                fn to_u128(self) -> u128 {
+                    u128::from(self)
+                }
+
+                fn from_u128(value: u128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_flag!(u8, u16, u32, u64, u128);
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod util__wincounter__flag__tests {
+    use super::*;
+
+    #[test]
+    fn capacity() {
+        assert_eq!(8, u8::CAPACITY);
+        assert_eq!(16, u16::CAPACITY);
+        assert_eq!(32, u32::CAPACITY);
+        assert_eq!(64, u64::CAPACITY);
+        assert_eq!(128, u128::CAPACITY);
+    }
+
+    #[test]
+    fn from_index() {
+        assert_eq!(Some(0b1_u16), u16::from_index(0));
+        assert_eq!(Some(0b1000_0000_0000_0000_u16), u16::from_index(15));
+        assert_eq!(None, u16::from_index(16));
+        assert_eq!(Some(1_u64 << 63), u64::from_index(63));
+        assert_eq!(None, u64::from_index(64));
+        assert_eq!(None, u8::from_index(usize::MAX));
+    }
+
+    #[test]
+    fn players() {
+        assert_eq!(0, 0_u32.players());
+        assert_eq!(3, 0b1011_u32.players());
+    }
+
+    #[test]
+    fn lowest_index() {
+        assert_eq!(None, 0_u8.lowest_index());
+        assert_eq!(Some(2), 0b1100_u8.lowest_index());
+    }
+
+    #[test]
+    fn contains_index() {
+        assert!(0b0101_u16.contains_index(2));
+        assert!(!0b0101_u16.contains_index(1));
+        assert!(!0b0101_u16.contains_index(99));
+    }
+
+    #[test]
+    fn indexes() {
+        assert_eq!(0, 0_u16.indexes().count());
+        assert_eq!(
+            vec![0, 31, 127],
+            (1_u128 | 1 << 31 | 1 << 127).indexes().collect::<Vec<_>>()
+        );
+        assert_eq!(2, 0b1010_u8.indexes().len());
+    }
+
+    #[test]
+    fn u128_round_trip() {
+        assert_eq!(0b1010, 0b1010_u8.to_u128());
+        assert_eq!(Some(0b1010_u8), u8::from_u128(0b1010));
+        assert_eq!(None, u8::from_u128(0b1_0000_0000));
+    }
+}
@@ -0,0 +1,300 @@
+//! Who beat whom, pair by pair, in a multi-way game.
+//!
+//! A `WinResults` says how often each player won, but not who they were winning against. The
+//! flag patterns already know that, though. Every outcome that one player won and another
+//! didn't is a win for the first over the second, so a single pass over a `Wins` gives a
+//! [`HeadsUp`] for every pair of players, without running the simulation again.
+//!
+//! ```
+//! use wincounter::head_to_head::HeadToHead;
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let mut wins = Wins::default();
+//! wins.add_x(Win::FIRST, 50);
+//! wins.add_x(Win::SECOND, 30);
+//! wins.add_x(Win::THIRD, 10);
+//! wins.add_x(Win::FIRST | Win::SECOND, 10);
+//!
+//! let matrix = HeadToHead::from_wins(&wins, 3);
+//! let first_v_second = matrix.get(0, 1).unwrap();
+//!
+//! assert_eq!(50, first_v_second.first_wins);
+//! assert_eq!(30, first_v_second.second_wins);
+//! assert_eq!(10, first_v_second.ties);
+//! // The third player won the other ten, so neither of them did.
+//! assert_eq!(10, first_v_second.no_winner);
+//! assert!(matrix.beats(0, 1));
+//! assert_eq!(vec![0, 1, 2], matrix.ranking());
+//! ```
+use crate::flag::Flag;
+use crate::heads_up::HeadsUp;
+use crate::results::label_or;
+use crate::wins::Wins;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// `cells[i][j]` is player `i`'s record against player `j`, with `i` as the first player. So
+/// `first_wins` is how often `i` won and `j` didn't, `second_wins` the other way around, `ties`
+/// how often they both won, and `no_winner` how often neither of them did, which includes the
+/// outcomes nobody won at all. The diagonal is left empty.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HeadToHead {
+    pub player_count: usize,
+    pub cells: Vec<Vec<HeadsUp>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl HeadToHead {
+    /// Players at or past `player_count` are ignored, the same as in `WinResults::from_wins()`.
+    /// Only the top half of the matrix is counted. The bottom half is the same records flipped.
+    #[must_use]
+    #[allow(clippy::needless_range_loop)]
+    pub fn from_wins<F: Flag>(wins: &Wins<F>, player_count: usize) -> Self {
+        let mut cells = vec![vec![HeadsUp::default(); player_count]; player_count];
+        for (count, x) in wins.iter() {
+            for i in 0..player_count {
+                let won = count.contains_index(i);
+                for j in (i + 1)..player_count {
+                    let cell = &mut cells[i][j];
+                    match (won, count.contains_index(j)) {
+                        (true, true) => cell.ties += x,
+                        (true, false) => cell.first_wins += x,
+                        (false, true) => cell.second_wins += x,
+                        (false, false) => cell.no_winner += x,
+                    }
+                }
+            }
+        }
+        for i in 0..player_count {
+            for j in (i + 1)..player_count {
+                cells[i][j].no_winner += wins.no_winner();
+                cells[j][i] = flip(cells[i][j]);
+            }
+        }
+        Self {
+            player_count,
+            cells,
+            labels: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Player `i`'s record against player `j`, or `None` if they're the same player or either
+    /// of them isn't in the game.
+    #[must_use]
+    pub fn get(&self, i: usize, j: usize) -> Option<HeadsUp> {
+        if i == j {
+            return None;
+        }
+        self.cells.get(i).and_then(|row| row.get(j)).copied()
+    }
+
+    /// True if `i` won more of their outcomes against `j` than `j` won against them.
+    #[must_use]
+    pub fn beats(&self, i: usize, j: usize) -> bool {
+        self.get(i, j)
+            .is_some_and(|hup| hup.first_wins > hup.second_wins)
+    }
+
+    /// How many of the other players `i` beats.
+    #[must_use]
+    pub fn dominance(&self, i: usize) -> usize {
+        (0..self.player_count).filter(|j| self.beats(i, *j)).count()
+    }
+
+    /// Player indexes, most dominant first. Players who beat the same number of opponents stay
+    /// in index order.
+    #[must_use]
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut players: Vec<usize> = (0..self.player_count).collect();
+        players.sort_by_key(|i| std::cmp::Reverse(self.dominance(*i)));
+        players
+    }
+
+    /// The player's label, or `#1` etc. if they don't have one, which keeps the table narrow.
+    #[must_use]
+    pub fn short_label(&self, i: usize) -> String {
+        label_or(&self.labels, i, "#")
+    }
+}
+
+/// Swaps the two players around.
+fn flip(hup: HeadsUp) -> HeadsUp {
+    HeadsUp::new(hup.second_wins, hup.first_wins, hup.ties).with_no_winner(hup.no_winner)
+}
+
+impl<F: Flag> Wins<F> {
+    /// Shorthand for `HeadToHead::from_wins()`.
+    #[must_use]
+    pub fn head_to_head(&self, player_count: usize) -> HeadToHead {
+        HeadToHead::from_wins(self, player_count)
+    }
+}
+
+/// A table of how often the row player beat the column player.
+///
+/// ```txt
+///         #1      #2      #3
+/// #1       -  50.00%  60.00%
+/// #2  30.00%       -  40.00%
+/// #3  10.00%  10.00%       -
+/// ```
+impl Display for HeadToHead {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let labels: Vec<String> = (0..self.player_count)
+            .map(|i| self.short_label(i))
+            .collect();
+        let first = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let width = first.max("100.00%".len());
+        write!(f, "{:first$}", "")?;
+        for label in &labels {
+            write!(f, " {label:>width$}")?;
+        }
+        writeln!(f)?;
+        for (i, label) in labels.iter().enumerate() {
+            write!(f, "{label:first$}")?;
+            for j in 0..self.player_count {
+                let cell = self.get(i, j).map_or("-".to_string(), |hup| {
+                    format!("{:.2}%", hup.percentage_first())
+                });
+                write!(f, " {cell:>width$}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__head_to_head__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn three_way() -> Wins {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 50);
+        wins.add_x(Win::SECOND, 30);
+        wins.add_x(Win::THIRD, 10);
+        wins.add_x(Win::FIRST | Win::SECOND, 10);
+        wins
+    }
+
+    #[test]
+    fn from_wins() {
+        let matrix = three_way().head_to_head(3);
+
+        assert_eq!(
+            Some(HeadsUp::new(60, 10, 0).with_no_winner(30)),
+            matrix.get(0, 2)
+        );
+        assert_eq!(
+            Some(HeadsUp::new(10, 60, 0).with_no_winner(30)),
+            matrix.get(2, 0)
+        );
+        assert_eq!(
+            Some(HeadsUp::new(40, 10, 0).with_no_winner(50)),
+            matrix.get(1, 2)
+        );
+        for i in 0..3 {
+            for j in 0..3 {
+                if i != j {
+                    assert_eq!(100, matrix.get(i, j).unwrap().total());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_wins__heads_up() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 1_365_284);
+        wins.add_x(Win::SECOND, 314_904);
+        wins.add_x(Win::FIRST | Win::SECOND, 32_116);
+        wins.add_no_winner_x(4);
+
+        assert_eq!(
+            Some(wins.results_heads_up()),
+            wins.head_to_head(2).get(0, 1)
+        );
+    }
+
+    #[test]
+    fn from_wins__ignores_stray_players() {
+        let wins = Wins::from(vec![Win::FIRST, Win::FIFTH]);
+
+        let matrix = wins.head_to_head(2);
+
+        assert_eq!(2, matrix.cells.len());
+        assert_eq!(
+            Some(HeadsUp::new(1, 0, 0).with_no_winner(1)),
+            matrix.get(0, 1)
+        );
+    }
+
+    #[test]
+    fn get() {
+        let matrix = three_way().head_to_head(3);
+
+        assert_eq!(None, matrix.get(1, 1));
+        assert_eq!(None, matrix.get(0, 3));
+        assert_eq!(None, HeadToHead::default().get(0, 1));
+    }
+
+    #[test]
+    fn beats_and_ranking() {
+        let matrix = three_way().head_to_head(3);
+
+        assert!(matrix.beats(0, 1));
+        assert!(!matrix.beats(1, 0));
+        assert!(!matrix.beats(0, 0));
+        assert_eq!(2, matrix.dominance(0));
+        assert_eq!(0, matrix.dominance(2));
+        assert_eq!(vec![0, 1, 2], matrix.ranking());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "        #1      #2      #3\n\
+             #1       -  50.00%  60.00%\n\
+             #2  30.00%       -  40.00%\n\
+             #3  10.00%  10.00%       -\n",
+            three_way().head_to_head(3).to_string()
+        );
+    }
+
+    #[test]
+    fn display__labels() {
+        let matrix = three_way()
+            .head_to_head(2)
+            .with_labels(["Negreanu", "Hansen"]);
+
+        assert_eq!(
+            "         Negreanu   Hansen\n\
+             Negreanu        -   50.00%\n\
+             Hansen     30.00%        -\n",
+            matrix.to_string()
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let matrix = three_way().head_to_head(2);
+
+        let json = serde_json::to_string(&matrix).unwrap();
+
+        assert_eq!(matrix, serde_json::from_str(&json).unwrap());
+    }
+}
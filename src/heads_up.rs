@@ -1,3 +1,4 @@
+use crate::results::WinResults;
 use crate::util::Util;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -17,6 +18,20 @@ use std::fmt;
 /// let the_hand = HeadsUp::new(1365284, 314904, 32116);
 /// assert_eq!("79.73% (1365284), 18.39% (314904), 1.88% (32116)", the_hand.to_string());
 /// ```
+///
+/// `no_winner` counts the outcomes neither player won. It's left out of the report, and out of
+/// the JSON, when it's zero, which it is unless you ask for it with `with_no_winner()`.
+///
+/// ```
+/// use wincounter::heads_up::HeadsUp;
+///
+/// let void = HeadsUp::new(45, 45, 5).with_no_winner(5);
+/// assert_eq!(100, void.total());
+/// assert_eq!(
+///     "45.00% (45), 45.00% (45), 5.00% (5), no winner 5.00% (5)",
+///     void.to_string()
+/// );
+/// ```
 #[derive(
     Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
 )]
@@ -24,6 +39,8 @@ pub struct HeadsUp {
     pub first_wins: usize,
     pub second_wins: usize,
     pub ties: usize,
+    #[serde(default, skip_serializing_if = "Util::is_zero")]
+    pub no_winner: usize,
 }
 
 impl HeadsUp {
@@ -33,9 +50,16 @@ impl HeadsUp {
             first_wins,
             second_wins,
             ties,
+            no_winner: 0,
         }
     }
 
+    #[must_use]
+    pub fn with_no_winner(mut self, no_winner: usize) -> Self {
+        self.no_winner = no_winner;
+        self
+    }
+
     #[must_use]
     pub fn percentage_first(&self) -> f32 {
         Util::calculate_percentage(self.first_wins, self.total())
@@ -46,6 +70,12 @@ impl HeadsUp {
         Util::calculate_percentage(self.first_wins + self.ties, self.total())
     }
 
+    /// The first player's equity, counting each tie as half a win.
+    #[must_use]
+    pub fn percentage_first_equity(&self) -> f32 {
+        Util::calculate_percentage(self.first_wins * 2 + self.ties, self.total() * 2)
+    }
+
     #[must_use]
     pub fn percentage_second(&self) -> f32 {
         Util::calculate_percentage(self.second_wins, self.total())
@@ -56,20 +86,84 @@ impl HeadsUp {
         Util::calculate_percentage(self.second_wins + self.ties, self.total())
     }
 
+    /// The second player's equity, counting each tie as half a win.
+    #[must_use]
+    pub fn percentage_second_equity(&self) -> f32 {
+        Util::calculate_percentage(self.second_wins * 2 + self.ties, self.total() * 2)
+    }
+
     #[must_use]
     pub fn percentage_ties(&self) -> f32 {
         Util::calculate_percentage(self.ties, self.total())
     }
 
+    #[must_use]
+    pub fn percentage_no_winner(&self) -> f32 {
+        Util::calculate_percentage(self.no_winner, self.total())
+    }
+
     #[must_use]
     pub fn percentage_total(&self) -> f32 {
-        let x = self.percentage_first() + self.percentage_second() + self.percentage_ties();
+        let x = self.percentage_first()
+            + self.percentage_second()
+            + self.percentage_ties()
+            + self.percentage_no_winner();
         (x * 100.0).round() / 100.0
     }
 
     #[must_use]
     pub fn total(&self) -> usize {
-        self.first_wins + self.second_wins + self.ties
+        self.first_wins + self.second_wins + self.ties + self.no_winner
+    }
+
+    /// `, no winner 5.00% (5)`, or nothing at all if there weren't any.
+    fn no_winner_to_string(&self) -> String {
+        if self.no_winner == 0 {
+            String::new()
+        } else {
+            format!(
+                ", no winner {:.2}% ({})",
+                self.percentage_no_winner(),
+                self.no_winner
+            )
+        }
+    }
+
+    /// `HeadsUp` doesn't hold on to names, since it's `Copy` and I'd like it to stay that way,
+    /// so they get passed in here instead. For everything else, use
+    /// [`WinResults::with_labels()`] on `WinResults::from(&hup)`.
+    ///
+    /// ```
+    /// use wincounter::heads_up::HeadsUp;
+    ///
+    /// let the_hand = HeadsUp::new(1365284, 314904, 32116);
+    /// assert_eq!(
+    ///     "Negreanu 79.73% (1365284), Hansen 18.39% (314904), ties 1.88% (32116)",
+    ///     the_hand.to_string_labeled("Negreanu", "Hansen")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_string_labeled(&self, first: &str, second: &str) -> String {
+        format!(
+            "{first} {:.2}% ({}), {second} {:.2}% ({}), ties {:.2}% ({}){}",
+            self.percentage_first(),
+            self.first_wins,
+            self.percentage_second(),
+            self.second_wins,
+            self.percentage_ties(),
+            self.ties,
+            self.no_winner_to_string()
+        )
+    }
+}
+
+/// Takes the first two players from a `WinResults`. The ties are the first player's ties,
+/// which is the same as the second player's as long as there were only two players.
+impl From<&WinResults> for HeadsUp {
+    fn from(results: &WinResults) -> Self {
+        let (first_wins, ties) = results.wins_and_ties(0);
+        let (second_wins, _) = results.wins_and_ties(1);
+        HeadsUp::new(first_wins, second_wins, ties).with_no_winner(results.no_winner)
     }
 }
 
@@ -77,19 +171,26 @@ impl fmt::Display for HeadsUp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{:.2}% ({}), {:.2}% ({}), {:.2}% ({})",
+            "{:.2}% ({}), {:.2}% ({}), {:.2}% ({}){}",
             self.percentage_first(),
             self.first_wins,
             self.percentage_second(),
             self.second_wins,
             self.percentage_ties(),
-            self.ties
+            self.ties,
+            self.no_winner_to_string()
         )
     }
 }
 
 #[cfg(test)]
-#[allow(non_snake_case)]
+#[allow(
+    non_snake_case,
+    clippy::float_cmp,
+    clippy::semicolon_if_nothing_returned,
+    clippy::unreadable_literal,
+    clippy::unwrap_used
+)]
 mod util__wincounter__heads_up__tests {
     use super::*;
 
@@ -117,6 +218,13 @@ mod util__wincounter__heads_up__tests {
         assert_eq!(60.0, simple().percentage_first_cumulative());
     }
 
+    #[test]
+    fn percentage_first_equity() {
+        assert_eq!(80.67155, the_hand().percentage_first_equity());
+        assert_eq!(50.0, simple().percentage_first_equity());
+        assert_eq!(0.0, HeadsUp::default().percentage_first_equity());
+    }
+
     #[test]
     fn percentage_second() {
         assert_eq!(18.39066, the_hand().percentage_second());
@@ -129,6 +237,12 @@ mod util__wincounter__heads_up__tests {
         assert_eq!(60.0, simple().percentage_second_cumulative());
     }
 
+    #[test]
+    fn percentage_second_equity() {
+        assert_eq!(19.32846, the_hand().percentage_second_equity());
+        assert_eq!(50.0, simple().percentage_second_equity());
+    }
+
     #[test]
     fn percentage_ties() {
         assert_eq!(1.8756015, the_hand().percentage_ties());
@@ -148,6 +262,37 @@ mod util__wincounter__heads_up__tests {
         assert_eq!(1_712_304, hup.total())
     }
 
+    #[test]
+    fn no_winner() {
+        let hup = HeadsUp::new(40, 40, 10).with_no_winner(10);
+
+        assert_eq!(100, hup.total());
+        assert_eq!(10.0, hup.percentage_no_winner());
+        assert_eq!(45.0, hup.percentage_first_equity());
+        assert_eq!(100.0, hup.percentage_total());
+        assert_eq!(
+            "A 40.00% (40), B 40.00% (40), ties 10.00% (10), no winner 10.00% (10)",
+            hup.to_string_labeled("A", "B")
+        );
+        assert_eq!(0.0, HeadsUp::default().percentage_no_winner());
+    }
+
+    #[test]
+    fn no_winner__serde() {
+        let hup = HeadsUp::new(40, 40, 10).with_no_winner(10);
+        let json = serde_json::to_string(&hup).unwrap();
+
+        assert!(!serde_json::to_string(&the_hand())
+            .unwrap()
+            .contains("no_winner"));
+        assert_eq!(hup, serde_json::from_str(&json).unwrap());
+        assert_eq!(
+            the_hand(),
+            serde_json::from_str(r#"{"first_wins":1365284,"second_wins":314904,"ties":32116}"#)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn remote_test() {
         // stats from https://tools.timodenk.com/poker-odds-pre-flop
@@ -160,6 +305,19 @@ mod util__wincounter__heads_up__tests {
         assert_eq!(100.0, hup.percentage_total());
     }
 
+    #[test]
+    fn from__win_results() {
+        let results = WinResults {
+            case_count: 1_712_304,
+            player_count: 2,
+            v: vec![(1_365_284, 32_116), (314_904, 32_116)],
+            ..Default::default()
+        };
+
+        assert_eq!(the_hand(), HeadsUp::from(&results));
+        assert_eq!(HeadsUp::default(), HeadsUp::from(&WinResults::default()));
+    }
+
     #[test]
     fn display() {
         assert_eq!(
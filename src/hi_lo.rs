@@ -0,0 +1,494 @@
+//! Split pot games, like Omaha Hi/Lo and Stud-8.
+//!
+//! In a high/low game, every pot is two pots: half goes to the best high hand, and half to the
+//! best qualifying low. If nobody has a low, the high hand takes the lot. One flag per outcome
+//! can't say any of that, so [`HiLoWins`] records two: who won the high, and who won the low,
+//! with an empty low flag for "no qualifying low".
+//!
+//! What a player cares about in these games isn't just how often they win, but how they win.
+//! Scooping the whole pot is the goal, and getting quartered, where you split your half and end
+//! up with a quarter of the pot, is the classic way to lose money on a winning hand.
+//! [`HiLoResults`] has all of that for every player, along with their overall equity.
+//!
+//! ```
+//! use wincounter::hi_lo::HiLoWins;
+//! use wincounter::win::Win;
+//!
+//! let mut wins = HiLoWins::default();
+//! // The first player scoops, high and low.
+//! wins.add_x(Win::FIRST, Win::FIRST, 2);
+//! // The first player wins the high, with no qualifying low.
+//! wins.add(Win::FIRST, 0);
+//! // The second player wins the high, and the first and third split the low.
+//! wins.add(Win::SECOND, Win::FIRST | Win::THIRD);
+//!
+//! let results = wins.results(3);
+//!
+//! assert_eq!(75.0, results.scoop_percentage(0));
+//! assert_eq!(25.0, results.quartered_percentage(0));
+//! assert_eq!(25.0, results.high_only_percentage(1));
+//! assert_eq!(25.0, results.low_only_percentage(2));
+//! assert_eq!(81.25, results.equity_percentage(0));
+//! assert_eq!(25.0, results.no_low_percentage());
+//! ```
+use crate::flag::Flag;
+use crate::results::label_or;
+use crate::util::Util;
+use crate::wins::Wins;
+use crate::PlayerFlag;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// A count for every distinct pair of high and low winners, the same way `Wins` counts single
+/// flags. `Default` is only for the `u16` version, like `Wins`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HiLoWins<F: Flag = PlayerFlag> {
+    counts: BTreeMap<(F, F), usize>,
+}
+
+impl<F: Flag> HiLoWins<F> {
+    #[must_use]
+    pub fn new() -> Self {
+        HiLoWins {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an outcome. `low` is empty if nobody qualified for the low.
+    pub fn add(&mut self, high: F, low: F) {
+        self.add_x(high, low, 1);
+    }
+
+    /// Adds an outcome x number of times.
+    pub fn add_x(&mut self, high: F, low: F, x: usize) {
+        if x > 0 {
+            *self.counts.entry((high, low)).or_default() += x;
+        }
+    }
+
+    pub fn extend(&mut self, other: &HiLoWins<F>) {
+        for (high, low, x) in other.iter() {
+            self.add_x(high, low, x);
+        }
+    }
+
+    /// The number of times that exact pair of winners was recorded.
+    #[must_use]
+    pub fn count(&self, high: F, low: F) -> usize {
+        self.counts.get(&(high, low)).copied().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Iterates over each distinct pair of high and low winners, in order, along with the
+    /// number of times it was recorded.
+    pub fn iter(&self) -> impl Iterator<Item = (F, F, usize)> + '_ {
+        self.counts.iter().map(|((high, low), x)| (*high, *low, *x))
+    }
+
+    /// The total number of outcomes recorded.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Just the high half, as a plain `Wins`.
+    #[must_use]
+    pub fn high(&self) -> Wins<F> {
+        let mut wins = Wins::new();
+        for (high, _, x) in self.iter() {
+            if high.is_none() {
+                wins.add_no_winner_x(x);
+            } else {
+                wins.add_x(high, x);
+            }
+        }
+        wins
+    }
+
+    /// Just the low half, as a plain `Wins`. Outcomes with no qualifying low are its
+    /// no-winners.
+    #[must_use]
+    pub fn low(&self) -> Wins<F> {
+        let mut wins = Wins::new();
+        for (_, low, x) in self.iter() {
+            if low.is_none() {
+                wins.add_no_winner_x(x);
+            } else {
+                wins.add_x(low, x);
+            }
+        }
+        wins
+    }
+
+    /// Players at or past `player_count` are ignored, the same as in `WinResults::from_wins()`,
+    /// though they still take their cut of any half they tied for.
+    #[must_use]
+    pub fn results(&self, player_count: usize) -> HiLoResults {
+        HiLoResults::from_wins(self, player_count)
+    }
+}
+
+impl Default for HiLoWins {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How one player did across every outcome.
+///
+/// - `scoops` is how often they took the whole pot by themselves.
+/// - `high_only` is how often they got some of the high and none of the low, and `low_only`
+///   the other way around.
+/// - `quartered` is how often they split one half two ways and got none of the other half,
+///   which somebody else won, leaving them a quarter of the pot.
+/// - `equity` is their share of the pots, where each outcome is worth one pot, the same as
+///   `WinResults::equity()`.
+///
+/// Winning the high alone with no qualifying low is a scoop, not a high only. Splitting the
+/// whole pot because there's no low is a high only, and the same goes for the low when there's
+/// no high winner.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HiLoRecord {
+    pub scoops: usize,
+    pub high_only: usize,
+    pub low_only: usize,
+    pub quartered: usize,
+    pub equity: f64,
+}
+
+/// The per-player report for a `HiLoWins`. `no_low` is how many of the cases had no qualifying
+/// low.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HiLoResults {
+    pub case_count: usize,
+    pub player_count: usize,
+    pub no_low: usize,
+    pub players: Vec<HiLoRecord>,
+    pub labels: Vec<String>,
+}
+
+impl HiLoResults {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_wins<F: Flag>(wins: &HiLoWins<F>, player_count: usize) -> HiLoResults {
+        let mut results = HiLoResults {
+            player_count,
+            players: vec![HiLoRecord::default(); player_count],
+            ..Default::default()
+        };
+        for (high, low, x) in wins.iter() {
+            results.case_count += x;
+            if low.is_none() {
+                results.no_low += x;
+            }
+            for (i, record) in results.players.iter_mut().enumerate() {
+                let (numerator, denominator) = share(high, low, i);
+                if numerator == 0 {
+                    continue;
+                }
+                record.equity += (numerator * x) as f64 / denominator as f64;
+                if numerator == denominator {
+                    record.scoops += x;
+                }
+                let one_half = high.contains_index(i) != low.contains_index(i);
+                if one_half && !high.is_none() && !low.is_none() && numerator * 4 == denominator {
+                    record.quartered += x;
+                }
+                match (high.contains_index(i), low.contains_index(i)) {
+                    (true, false) if numerator != denominator => record.high_only += x,
+                    (false, true) if numerator != denominator => record.low_only += x,
+                    _ => {},
+                }
+            }
+        }
+        results
+    }
+
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The player's label, or `Player #1` etc. without one.
+    #[must_use]
+    pub fn label(&self, player_index: usize) -> String {
+        label_or(&self.labels, player_index, "Player #")
+    }
+
+    #[must_use]
+    pub fn record(&self, player_index: usize) -> HiLoRecord {
+        self.players.get(player_index).copied().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn scoop_percentage(&self, player_index: usize) -> f32 {
+        Util::calculate_percentage(self.record(player_index).scoops, self.case_count)
+    }
+
+    #[must_use]
+    pub fn high_only_percentage(&self, player_index: usize) -> f32 {
+        Util::calculate_percentage(self.record(player_index).high_only, self.case_count)
+    }
+
+    #[must_use]
+    pub fn low_only_percentage(&self, player_index: usize) -> f32 {
+        Util::calculate_percentage(self.record(player_index).low_only, self.case_count)
+    }
+
+    #[must_use]
+    pub fn quartered_percentage(&self, player_index: usize) -> f32 {
+        Util::calculate_percentage(self.record(player_index).quartered, self.case_count)
+    }
+
+    /// The player's equity as a percentage of all cases. Across every player, these add up to
+    /// 100%, as long as every outcome had a high winner.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn equity_percentage(&self, player_index: usize) -> f32 {
+        match self.case_count {
+            0 => 0_f32,
+            total => (self.record(player_index).equity * 100.0 / total as f64) as f32,
+        }
+    }
+
+    #[must_use]
+    pub fn no_low_percentage(&self) -> f32 {
+        Util::calculate_percentage(self.no_low, self.case_count)
+    }
+}
+
+/// The player's share of the pot for one outcome, as a fraction. Half goes to the high and half
+/// to the low, unless one of them has no winner and the other takes the lot, and each half is
+/// split evenly between whoever won it.
+fn share<F: Flag>(high: F, low: F, i: usize) -> (usize, usize) {
+    let highs = high.players();
+    let lows = low.players();
+    let in_high = usize::from(high.contains_index(i));
+    let in_low = usize::from(low.contains_index(i));
+    if lows == 0 {
+        (in_high, highs.max(1))
+    } else if highs == 0 {
+        (in_low, lows)
+    } else {
+        (in_high * lows + in_low * highs, highs * lows * 2)
+    }
+}
+
+/// One line per player, then how often there was no low.
+///
+/// ```txt
+/// Player #1 81.25% equity, scoop 75.00%, high only 0.00%, low only 25.00%, quartered 25.00%
+/// ...
+/// No low 25.00%
+/// ```
+impl Display for HiLoResults {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for i in 0..self.player_count {
+            writeln!(
+                f,
+                "{} {:.2}% equity, scoop {:.2}%, high only {:.2}%, low only {:.2}%, quartered {:.2}%",
+                self.label(i),
+                self.equity_percentage(i),
+                self.scoop_percentage(i),
+                self.high_only_percentage(i),
+                self.low_only_percentage(i),
+                self.quartered_percentage(i)
+            )?;
+        }
+        writeln!(f, "No low {:.2}%", self.no_low_percentage())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp)]
+mod util__wincounter__hi_lo__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn hi_lo() -> HiLoWins {
+        let mut wins = HiLoWins::default();
+        wins.add_x(Win::FIRST, Win::FIRST, 2);
+        wins.add(Win::FIRST, 0);
+        wins.add(Win::SECOND, Win::FIRST | Win::THIRD);
+        wins
+    }
+
+    #[test]
+    fn add() {
+        let wins = hi_lo();
+
+        assert_eq!(4, wins.len());
+        assert_eq!(2, wins.count(Win::FIRST, Win::FIRST));
+        assert_eq!(1, wins.count(Win::FIRST, 0));
+        assert_eq!(0, wins.count(Win::SECOND, 0));
+        assert!(!wins.is_empty());
+        assert!(HiLoWins::default().is_empty());
+    }
+
+    #[test]
+    fn add_x__zero() {
+        let mut wins = HiLoWins::default();
+
+        wins.add_x(Win::FIRST, 0, 0);
+
+        assert!(wins.is_empty());
+    }
+
+    #[test]
+    fn extend() {
+        let mut wins = hi_lo();
+
+        wins.extend(&hi_lo());
+
+        assert_eq!(8, wins.len());
+        assert_eq!(4, wins.count(Win::FIRST, Win::FIRST));
+    }
+
+    #[test]
+    fn high_and_low() {
+        let wins = hi_lo();
+
+        let high = wins.high();
+        let low = wins.low();
+
+        assert_eq!(3, high.count(Win::FIRST));
+        assert_eq!(1, high.count(Win::SECOND));
+        assert_eq!(0, high.no_winner());
+        assert_eq!(2, low.count(Win::FIRST));
+        assert_eq!(1, low.no_winner());
+        assert_eq!(4, low.len());
+    }
+
+    #[test]
+    fn results() {
+        let results = hi_lo().results(3);
+
+        assert_eq!(4, results.case_count);
+        assert_eq!(1, results.no_low);
+        assert_eq!(
+            HiLoRecord {
+                scoops: 3,
+                high_only: 0,
+                low_only: 1,
+                quartered: 1,
+                equity: 3.25,
+            },
+            results.record(0)
+        );
+        assert_eq!(
+            HiLoRecord {
+                scoops: 0,
+                high_only: 1,
+                low_only: 0,
+                quartered: 0,
+                equity: 0.5,
+            },
+            results.record(1)
+        );
+        assert_eq!(1, results.record(2).low_only);
+        assert_eq!(1, results.record(2).quartered);
+        assert_eq!(HiLoRecord::default(), results.record(3));
+    }
+
+    #[test]
+    fn results__equity_adds_up() {
+        let mut wins = hi_lo();
+        wins.add(Win::FIRST | Win::SECOND, Win::SECOND | Win::THIRD);
+        wins.add(Win::FIRST | Win::SECOND | Win::THIRD, 0);
+
+        let results = wins.results(3);
+
+        let total: f32 = (0..3).map(|i| results.equity_percentage(i)).sum();
+        assert_eq!(100.0, total.round());
+    }
+
+    #[test]
+    fn results__split_with_no_low() {
+        let mut wins = HiLoWins::default();
+        wins.add(Win::FIRST | Win::SECOND, 0);
+        wins.add(Win::FIRST | Win::SECOND | Win::THIRD | Win::FORTH, 0);
+
+        let results = wins.results(4);
+
+        assert_eq!(2, results.record(0).high_only);
+        assert_eq!(0, results.record(0).scoops);
+        assert_eq!(0, results.record(3).quartered);
+        assert_eq!(0.25, results.record(3).equity);
+        assert_eq!(0.75, results.record(0).equity);
+    }
+
+    #[test]
+    fn results__three_quarters() {
+        let mut wins = HiLoWins::default();
+        wins.add(Win::FIRST, Win::FIRST | Win::SECOND);
+
+        let results = wins.results(2);
+
+        assert_eq!(0.75, results.record(0).equity);
+        assert_eq!(0, results.record(0).scoops);
+        assert_eq!(0, results.record(0).high_only);
+        assert_eq!(1, results.record(1).quartered);
+        assert_eq!(1, results.record(1).low_only);
+    }
+
+    #[test]
+    fn results__no_high() {
+        let mut wins = HiLoWins::default();
+        wins.add(0, Win::FIRST);
+        wins.add(0, Win::FIRST | Win::SECOND);
+
+        let results = wins.results(2);
+
+        assert_eq!(1.5, results.record(0).equity);
+        assert_eq!(1, results.record(0).scoops);
+        assert_eq!(1, results.record(0).low_only);
+        assert_eq!(0, results.record(0).quartered);
+        assert_eq!(
+            100.0,
+            results.equity_percentage(0) + results.equity_percentage(1)
+        );
+    }
+
+    #[test]
+    fn results__split_both_halves() {
+        let mut wins = HiLoWins::default();
+        let four = Win::FIRST | Win::SECOND | Win::THIRD | Win::FORTH;
+        wins.add(four, four);
+
+        let results = wins.results(4);
+
+        // A quarter of the pot, but an eighth of each half, so not quartered.
+        assert_eq!(0.25, results.record(0).equity);
+        assert_eq!(0, results.record(0).quartered);
+    }
+
+    #[test]
+    fn results__empty() {
+        let results = HiLoWins::default().results(2);
+
+        assert_eq!(0.0, results.scoop_percentage(0));
+        assert_eq!(0.0, results.equity_percentage(0));
+        assert_eq!(0.0, results.no_low_percentage());
+    }
+
+    #[test]
+    fn display() {
+        let results = hi_lo().results(2).with_labels(["Hero"]);
+
+        assert_eq!(
+            "Hero 81.25% equity, scoop 75.00%, high only 0.00%, low only 25.00%, quartered 25.00%\n\
+             Player #2 12.50% equity, scoop 0.00%, high only 25.00%, low only 0.00%, quartered 0.00%\n\
+             No low 25.00%\n",
+            results.to_string()
+        );
+    }
+}
@@ -0,0 +1,610 @@
+//! Prize money instead of chips, for tournaments.
+//!
+//! In a cash game a chip is a chip. In a tournament it isn't: doubling your stack doesn't double
+//! what you'll get paid, and busting costs you more than the chips. The Independent Chip Model
+//! turns stacks into prize equity by guessing how likely each player is to finish in each
+//! paid place. [`Icm::equities()`] does the usual Malmuth–Harville guess, where the chance of
+//! finishing first is your share of the chips, and so on down the payouts with the players who
+//! are left.
+//!
+//! ```
+//! use wincounter::icm::Icm;
+//!
+//! let icm = Icm::new(vec![50.0, 30.0, 20.0], vec![50.0, 30.0, 20.0]);
+//!
+//! let equities = icm.equities().unwrap();
+//!
+//! assert_eq!(38.39, (equities[0] * 100.0).round() / 100.0);
+//! assert_eq!(100.0, equities.iter().sum::<f64>().round());
+//! ```
+//!
+//! [`Icm::equities_after()`] puts a `WinResults` and the [`Stakes`] of an all in through the
+//! model, so you get each player's prize equity once the hand is over, not their chips. And if
+//! you've simulated the rest of the tournament, [`Rankings::prize_equities()`] skips the guess
+//! and pays out the finishing orders you actually got.
+use crate::ev::{Stakes, StakesError};
+use crate::flag::Flag;
+use crate::ranking::Rankings;
+use crate::results::WinResults;
+use std::fmt::{Display, Formatter};
+
+/// Something wrong with the stacks, or with the hand they're going into.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IcmError {
+    /// A stack or payout is negative, infinite or not a number.
+    Negative,
+    /// There aren't any chips to share the prizes out by.
+    NoChips,
+    /// The number of stacks doesn't match the number of players in the results.
+    PlayerCount {
+        player_count: usize,
+        stacks: usize,
+    },
+    /// A player put more into the pot than they had.
+    OverStack {
+        player_index: usize,
+    },
+    Stakes(StakesError),
+}
+
+impl Display for IcmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IcmError::Negative => {
+                write!(f, "stacks and payouts have to be finite and not negative")
+            },
+            IcmError::NoChips => write!(f, "nobody has any chips"),
+            IcmError::PlayerCount {
+                player_count,
+                stacks,
+            } => write!(f, "{stacks} stacks for {player_count} players"),
+            IcmError::OverStack { player_index } => {
+                write!(
+                    f,
+                    "Player #{} put in more than their stack",
+                    player_index + 1
+                )
+            },
+            IcmError::Stakes(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for IcmError {}
+
+impl From<StakesError> for IcmError {
+    fn from(e: StakesError) -> Self {
+        IcmError::Stakes(e)
+    }
+}
+
+/// Everybody's chips, by player index, and what each place pays, first place first.
+///
+/// Players with no chips have already busted out, so they don't get paid. Every remaining
+/// player is run through every paid place, which is `n!/(n - places)!` orders, so keep it to a
+/// final table.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Icm {
+    pub stacks: Vec<f64>,
+    pub payouts: Vec<f64>,
+}
+
+impl Icm {
+    #[must_use]
+    pub fn new(stacks: Vec<f64>, payouts: Vec<f64>) -> Self {
+        Self { stacks, payouts }
+    }
+
+    /// # Errors
+    ///
+    /// Returns `IcmError::Negative` if any stack or payout is negative, infinite or not a number,
+    /// and `IcmError::NoChips` if the stacks are all empty.
+    pub fn validate(&self) -> Result<(), IcmError> {
+        if self
+            .stacks
+            .iter()
+            .chain(&self.payouts)
+            .any(|amount| !amount.is_finite() || *amount < 0.0)
+        {
+            return Err(IcmError::Negative);
+        }
+        if self.stacks.iter().sum::<f64>() <= 0.0 {
+            return Err(IcmError::NoChips);
+        }
+        Ok(())
+    }
+
+    /// The Malmuth–Harville chance of every player finishing in every paid place, so
+    /// `[i][p]` is the chance player `i` finishes in place `p + 1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IcmError` if the stacks don't pass [`Icm::validate()`].
+    pub fn place_probabilities(&self) -> Result<Vec<Vec<f64>>, IcmError> {
+        self.validate()?;
+        let places = self.payouts.len().min(self.stacks.len());
+        let mut probabilities = vec![vec![0.0; places]; self.stacks.len()];
+        let mut remaining: Vec<bool> = self.stacks.iter().map(|s| *s > 0.0).collect();
+        harville(&self.stacks, &mut remaining, 0, 1.0, &mut probabilities);
+        Ok(probabilities)
+    }
+
+    /// Every player's expected prize money.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IcmError` if the stacks don't pass [`Icm::validate()`].
+    pub fn equities(&self) -> Result<Vec<f64>, IcmError> {
+        Ok(self
+            .place_probabilities()?
+            .iter()
+            .map(|places| {
+                places
+                    .iter()
+                    .zip(&self.payouts)
+                    .map(|(probability, payout)| probability * payout)
+                    .sum()
+            })
+            .collect())
+    }
+
+    /// Every player's expected prize money after an all in, with `stacks` as they were
+    /// before it. For every way the hand can go in `results`, the pot goes to the winners,
+    /// split by `stakes.tie_split`, the stacks go through the model, and the equities are
+    /// averaged over how often it went that way. A hand nobody won gives everybody their
+    /// chips back. Anybody the hand busts finishes below everybody it doesn't, so they're paid
+    /// the lowest of the places that are left, and only the players still in go through the
+    /// model for the rest.
+    ///
+    /// ```
+    /// use wincounter::ev::Stakes;
+    /// use wincounter::icm::Icm;
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// // Three left, and the two big stacks are all in against each other.
+    /// let icm = Icm::new(vec![100.0, 100.0, 20.0], vec![60.0, 40.0]);
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 60);
+    /// wins.add_x(Win::SECOND, 40);
+    /// let stakes = Stakes::new(200.0, vec![100.0, 100.0, 0.0]);
+    ///
+    /// let after = icm.equities_after(&wins.results(3), &stakes).unwrap();
+    ///
+    /// // One of the big stacks is about to bust, which moves the short stack up a place.
+    /// assert_eq!(41.82, (after[2] * 100.0).round() / 100.0);
+    /// assert!(after[2] > icm.equities().unwrap()[2]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `IcmError` if the stacks don't pass [`Icm::validate()`], if there isn't a stack
+    /// for every player in `results`, if the stakes don't pass [`Stakes::validate()`], or if
+    /// anyone put in more than they had. A player who tied without `co_winners` saying who
+    /// with, as in results read back from CSV, is a `StakesError::UnknownTies`, since there's
+    /// no telling whose stacks the pot went to.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn equities_after(
+        &self,
+        results: &WinResults,
+        stakes: &Stakes,
+    ) -> Result<Vec<f64>, IcmError> {
+        self.validate()?;
+        if self.stacks.len() != results.player_count {
+            return Err(IcmError::PlayerCount {
+                player_count: results.player_count,
+                stacks: self.stacks.len(),
+            });
+        }
+        stakes.validate(results.player_count)?;
+        if let Some(player_index) =
+            (0..self.stacks.len()).find(|i| stakes.contribution(*i) > self.stacks[*i])
+        {
+            return Err(IcmError::OverStack { player_index });
+        }
+        if let Some(player_index) = (0..results.player_count)
+            .find(|i| results.wins_and_ties(*i).1 > 0 && results.complete_co_winners(*i).is_none())
+        {
+            return Err(StakesError::UnknownTies { player_index }.into());
+        }
+        if results.case_count == 0 {
+            return self.equities();
+        }
+
+        let behind: Vec<f64> = (0..self.stacks.len())
+            .map(|i| self.stacks[i] - stakes.contribution(i))
+            .collect();
+        let mut outcomes: Vec<(Vec<f64>, usize)> = vec![(self.stacks.clone(), results.no_winner)];
+        for i in 0..results.player_count {
+            let (wins, _) = results.wins_and_ties(i);
+            let mut stacks = behind.clone();
+            stacks[i] += stakes.pot;
+            outcomes.push((stacks, wins));
+            // Every tie is in every winner's co_winners, so it's only counted for the first.
+            let ties = results.complete_co_winners(i).into_iter().flatten();
+            for (others, x) in ties.filter(|(others, _)| others.iter().all(|j| *j > i)) {
+                let mut stacks = behind.clone();
+                for j in std::iter::once(i).chain(others.iter().copied()) {
+                    let others: Vec<usize> = std::iter::once(i)
+                        .chain(others.iter().copied())
+                        .filter(|k| *k != j)
+                        .collect();
+                    if let Some(stack) = stacks.get_mut(j) {
+                        *stack += stakes.tie_share(j, &others);
+                    }
+                }
+                outcomes.push((stacks, *x));
+            }
+        }
+
+        let mut equities = vec![0.0; self.stacks.len()];
+        for (stacks, x) in outcomes.into_iter().filter(|(_, x)| *x > 0) {
+            let after = self.equities_busting(stacks)?;
+            for (equity, e) in equities.iter_mut().zip(after) {
+                *equity += e * x as f64 / results.case_count as f64;
+            }
+        }
+        Ok(equities)
+    }
+
+    /// The equities once a hand has left everybody with `after`. The players it busted take the
+    /// places below the ones still in, bigger starting stacks first, and players who started
+    /// even split the places they take up between them.
+    #[allow(clippy::cast_precision_loss)]
+    fn equities_busting(&self, after: Vec<f64>) -> Result<Vec<f64>, IcmError> {
+        let mut busted: Vec<usize> = (0..after.len())
+            .filter(|i| self.stacks[*i] > 0.0 && after[*i] <= 0.0)
+            .collect();
+        busted.sort_by(|a, b| self.stacks[*b].total_cmp(&self.stacks[*a]));
+        let mut place = after.iter().filter(|stack| **stack > 0.0).count();
+        let payouts = self.payouts[..place.min(self.payouts.len())].to_vec();
+        let mut equities = Icm::new(after, payouts).equities()?;
+        for group in busted.chunk_by(|a, b| self.stacks[*a].total_cmp(&self.stacks[*b]).is_eq()) {
+            let paid: f64 = self.payouts.iter().skip(place).take(group.len()).sum();
+            for i in group {
+                equities[*i] = paid / group.len() as f64;
+            }
+            place += group.len();
+        }
+        Ok(equities)
+    }
+}
+
+/// Adds the chance of every remaining player finishing in `place`, given `probability` of
+/// getting this far, then does the same for the next place without them.
+fn harville(
+    stacks: &[f64],
+    remaining: &mut [bool],
+    place: usize,
+    probability: f64,
+    probabilities: &mut [Vec<f64>],
+) {
+    let places = probabilities.first().map_or(0, Vec::len);
+    if place >= places {
+        return;
+    }
+    let total: f64 = stacks
+        .iter()
+        .zip(remaining.iter())
+        .filter(|(_, left)| **left)
+        .map(|(stack, _)| stack)
+        .sum();
+    if total <= 0.0 {
+        return;
+    }
+    for i in 0..stacks.len() {
+        if !remaining[i] {
+            continue;
+        }
+        let p = probability * stacks[i] / total;
+        probabilities[i][place] += p;
+        remaining[i] = false;
+        harville(stacks, remaining, place + 1, p, probabilities);
+        remaining[i] = true;
+    }
+}
+
+impl<F: Flag> Rankings<F> {
+    /// Every player's expected prize money over the recorded finishing orders, with no model
+    /// at all. Players who tie for a place split the payouts for the places they take up, so
+    /// two players tied for first share first and second. Places past the end of `payouts`
+    /// pay nothing, and players at or past `player_count` are ignored, though they still take
+    /// their share.
+    ///
+    /// ```
+    /// use wincounter::ranking::Rankings;
+    /// use wincounter::win::Win;
+    ///
+    /// let mut rankings = Rankings::default();
+    /// rankings.add_x(&[Win::FIRST, Win::SECOND, Win::THIRD], 3).unwrap();
+    /// rankings.add(&[Win::SECOND | Win::THIRD, Win::FIRST]).unwrap();
+    ///
+    /// let equities = rankings.prize_equities(&[70.0, 30.0], 3);
+    ///
+    /// assert_eq!(vec![52.5, 35.0, 12.5], equities);
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn prize_equities(&self, payouts: &[f64], player_count: usize) -> Vec<f64> {
+        let mut equities = vec![0.0; player_count];
+        let total = self.len();
+        if total == 0 {
+            return equities;
+        }
+        for (order, x) in self.iter() {
+            let mut place = 0;
+            for group in order {
+                let k = group.players();
+                let paid: f64 = payouts.iter().skip(place).take(k).sum();
+                for i in group.indexes().filter(|i| *i < player_count) {
+                    equities[i] += paid / k as f64 * x as f64 / total as f64;
+                }
+                place += k;
+            }
+        }
+        equities
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp, clippy::unwrap_used)]
+mod util__wincounter__icm__tests {
+    use super::*;
+    use crate::heads_up::HeadsUp;
+    use crate::win::Win;
+
+    fn round(x: f64) -> f64 {
+        (x * 100.0).round() / 100.0
+    }
+
+    #[test]
+    fn validate() {
+        assert!(Icm::new(vec![1.0, 0.0], vec![1.0]).validate().is_ok());
+        assert_eq!(
+            Err(IcmError::Negative),
+            Icm::new(vec![1.0, -1.0], vec![1.0]).validate()
+        );
+        assert_eq!(
+            Err(IcmError::Negative),
+            Icm::new(vec![1.0], vec![f64::NAN]).validate()
+        );
+        assert_eq!(
+            Err(IcmError::Negative),
+            Icm::new(vec![1.0, f64::INFINITY], vec![1.0]).validate()
+        );
+        assert_eq!(
+            "nobody has any chips",
+            Icm::new(vec![0.0, 0.0], vec![1.0])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn place_probabilities() {
+        let icm = Icm::new(vec![50.0, 30.0, 20.0], vec![50.0, 30.0, 20.0]);
+
+        let probabilities = icm.place_probabilities().unwrap();
+
+        assert_eq!(0.5, probabilities[0][0]);
+        assert_eq!(0.34, round(probabilities[0][1]));
+        for p in 0..3 {
+            let total: f64 = probabilities.iter().map(|places| places[p]).sum();
+            assert_eq!(1.0, round(total));
+        }
+    }
+
+    #[test]
+    fn equities() {
+        let icm = Icm::new(vec![50.0, 30.0, 20.0], vec![50.0, 30.0, 20.0]);
+
+        let equities = icm.equities().unwrap();
+
+        assert_eq!(
+            vec![38.39, 32.75, 28.86],
+            equities.into_iter().map(round).collect::<Vec<f64>>()
+        );
+    }
+
+    #[test]
+    fn equities__heads_up() {
+        let icm = Icm::new(vec![75.0, 25.0], vec![100.0, 40.0]);
+
+        let equities = icm.equities().unwrap();
+
+        assert_eq!(vec![40.0 + 60.0 * 0.75, 40.0 + 60.0 * 0.25], equities);
+    }
+
+    #[test]
+    fn equities__busted_and_unpaid() {
+        let icm = Icm::new(vec![50.0, 0.0, 50.0], vec![100.0, 50.0, 25.0]);
+
+        let equities = icm.equities().unwrap();
+
+        assert_eq!(vec![75.0, 0.0, 75.0], equities);
+        assert_eq!(
+            vec![50.0, 50.0],
+            Icm::new(vec![10.0, 10.0], vec![100.0]).equities().unwrap()
+        );
+    }
+
+    #[test]
+    fn equities_after__winner_take_all() {
+        let icm = Icm::new(vec![100.0, 100.0], vec![100.0]);
+        let results = WinResults::from(&HeadsUp::new(60, 30, 10));
+        let stakes = Stakes::new(200.0, vec![100.0, 100.0]);
+
+        let equities = icm.equities_after(&results, &stakes).unwrap();
+
+        // The same as the chips, when only first gets paid and it's all on the line.
+        assert_eq!(
+            vec![65.0, 35.0],
+            equities.into_iter().map(round).collect::<Vec<f64>>()
+        );
+    }
+
+    #[test]
+    fn equities_after__bust_in_the_money() {
+        let icm = Icm::new(vec![100.0, 100.0], vec![60.0, 40.0]);
+        let results = WinResults::from(&HeadsUp::new(50, 50, 0));
+        let stakes = Stakes::new(200.0, vec![100.0, 100.0]);
+
+        assert_eq!(
+            vec![50.0, 50.0],
+            icm.equities_after(&results, &stakes).unwrap()
+        );
+
+        let icm = Icm::new(vec![100.0; 3], vec![50.0, 30.0, 20.0]);
+        let results =
+            WinResults::from_wins(&crate::wins::Wins::from(vec![Win::FIRST, Win::SECOND]), 3);
+        let stakes = Stakes::new(200.0, vec![100.0, 100.0, 0.0]);
+
+        let equities = icm.equities_after(&results, &stakes).unwrap();
+
+        assert_eq!(
+            vec![31.67, 31.67, 36.67],
+            equities.iter().copied().map(round).collect::<Vec<f64>>()
+        );
+        assert_eq!(100.0, round(equities.iter().sum()));
+    }
+
+    #[test]
+    fn equities_after__busting_together() {
+        let results = WinResults::from_wins(&crate::wins::Wins::from(vec![Win::FIRST]), 4);
+        let payouts = vec![40.0, 30.0, 20.0, 10.0];
+
+        // The bigger of the two stacks the leader busts finishes third.
+        let icm = Icm::new(vec![100.0, 60.0, 50.0, 10.0], payouts.clone());
+        let stakes = Stakes::new(170.0, vec![60.0, 60.0, 50.0, 0.0]);
+        let equities = icm.equities_after(&results, &stakes).unwrap();
+
+        assert_eq!(20.0, equities[1]);
+        assert_eq!(10.0, equities[2]);
+        assert_eq!(100.0, round(equities.iter().sum()));
+
+        // Even ones split third and fourth.
+        let icm = Icm::new(vec![100.0, 50.0, 50.0, 10.0], payouts);
+        let stakes = Stakes::new(150.0, vec![50.0, 50.0, 50.0, 0.0]);
+        let equities = icm.equities_after(&results, &stakes).unwrap();
+
+        assert_eq!(15.0, equities[1]);
+        assert_eq!(15.0, equities[2]);
+        assert_eq!(100.0, round(equities.iter().sum()));
+    }
+
+    #[test]
+    fn equities_after__no_winner() {
+        let icm = Icm::new(vec![60.0, 40.0], vec![70.0, 30.0]);
+        let results = WinResults::from(&HeadsUp::new(0, 0, 0).with_no_winner(5));
+        let stakes = Stakes::new(80.0, vec![40.0, 40.0]);
+
+        assert_eq!(
+            icm.equities().unwrap(),
+            icm.equities_after(&results, &stakes).unwrap()
+        );
+    }
+
+    #[test]
+    fn equities_after__three_way_tie() {
+        let mut wins = crate::wins::Wins::default();
+        wins.add(Win::FIRST | Win::SECOND | Win::THIRD);
+        let results = wins.results(3);
+        let icm = Icm::new(vec![30.0, 30.0, 30.0], vec![50.0, 30.0, 20.0]);
+        let stakes = Stakes::new(30.0, vec![10.0, 10.0, 10.0]);
+
+        let equities = icm.equities_after(&results, &stakes).unwrap();
+
+        assert_eq!(icm.equities().unwrap(), equities);
+    }
+
+    #[test]
+    fn equities_after__from_csv() {
+        let mut wins = crate::wins::Wins::default();
+        wins.add_x(Win::FIRST, 3);
+        wins.add(Win::SECOND | Win::THIRD);
+        let results = wins.results(3);
+        let icm = Icm::new(vec![30.0, 30.0, 30.0], vec![50.0, 30.0, 20.0]);
+        let stakes = Stakes::new(30.0, vec![10.0, 10.0, 10.0]);
+
+        let back = WinResults::from_csv(&results.to_csv()).unwrap();
+
+        assert!(icm.equities_after(&results, &stakes).is_ok());
+        assert_eq!(
+            Err(IcmError::Stakes(StakesError::UnknownTies {
+                player_index: 1
+            })),
+            icm.equities_after(&back, &stakes)
+        );
+    }
+
+    #[test]
+    fn equities_after__errors() {
+        let icm = Icm::new(vec![100.0, 100.0], vec![100.0]);
+        let results = WinResults::from(&HeadsUp::new(1, 1, 0));
+
+        assert_eq!(
+            Err(IcmError::OverStack { player_index: 1 }),
+            icm.equities_after(&results, &Stakes::new(250.0, vec![100.0, 150.0]))
+        );
+        assert_eq!(
+            Err(IcmError::Stakes(StakesError::Negative)),
+            icm.equities_after(&results, &Stakes::new(200.0, vec![100.0, -1.0]))
+        );
+        assert_eq!(
+            "3 stacks for 2 players",
+            Icm::new(vec![1.0; 3], vec![1.0])
+                .equities_after(&results, &Stakes::default())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn prize_equities() {
+        let mut rankings = Rankings::default();
+        rankings
+            .add_x(&[Win::FIRST, Win::SECOND, Win::THIRD], 3)
+            .unwrap();
+        rankings
+            .add(&[Win::SECOND | Win::THIRD, Win::FIRST])
+            .unwrap();
+
+        assert_eq!(
+            vec![52.5, 35.0, 12.5],
+            rankings.prize_equities(&[70.0, 30.0], 3)
+        );
+        assert_eq!(
+            vec![0.0, 0.0],
+            Rankings::default().prize_equities(&[1.0], 2)
+        );
+    }
+
+    #[test]
+    fn prize_equities__matches_harville() {
+        let icm = Icm::new(vec![50.0, 30.0, 20.0], vec![50.0, 30.0, 20.0]);
+        let mut rankings = Rankings::default();
+        // Every order, as often as Harville says it happens, out of 10,000.
+        for (a, b, c) in [
+            (0, 1, 2),
+            (0, 2, 1),
+            (1, 0, 2),
+            (1, 2, 0),
+            (2, 0, 1),
+            (2, 1, 0),
+        ] {
+            let stacks: [f64; 3] = [50.0, 30.0, 20.0];
+            let p = stacks[a] / 100.0 * stacks[b] / (100.0 - stacks[a]);
+            let order = [Win::from_index(a), Win::from_index(b), Win::from_index(c)];
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let x = (p * 10_000.0).round() as usize;
+            rankings.add_x(&order, x).unwrap();
+        }
+
+        let simulated = rankings.prize_equities(&icm.payouts, 3);
+
+        for (i, equity) in icm.equities().unwrap().iter().enumerate() {
+            assert_eq!(round(*equity), round(simulated[i]));
+        }
+    }
+}
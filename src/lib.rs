@@ -15,15 +15,41 @@
 //! The library uses bit flags ([`PlayerFlag`]) to represent winners and ties efficiently:
 //! - Each player is represented by a specific bit position (1st player = bit 0, 2nd player = bit 1, etc.)
 //! - When multiple bits are set, it indicates a tie between those players
-//! - Supports up to 16 players simultaneously
+//! - Supports up to 16 players by default, and up to 128 with a wider [`Flag`]
 //!
 //! ## Main Components
 //!
+//! - **[`binary`]** - A compact, streamable binary format for [`Wins`](wins::Wins)
+//! - **[`concurrent`]** - [`ConcurrentWins`](concurrent::ConcurrentWins), a counter that many threads can share
+//! - **[`confidence`]** - Standard errors and confidence intervals for sampled results
+//! - **[`convergence`]** - A [`StoppingRule`](convergence::StoppingRule) for Monte Carlo runs
+//! - **[`csv`]** - CSV import and export of results and tallies
+//! - **[`ev`]** - Expected chips and profit from [`Stakes`](ev::Stakes), and [`PotOdds`](ev::PotOdds)
+//! - **[`flag`]** - The [`Flag`] trait that lets the flag width go from `u8` to `u128`
 //! - **[`win`]** - Defines player bit flags (`FIRST`, `SECOND`, etc.) and utilities for
 //!   converting between player indices and flags
 //! - **[`wins`]** - The [`Wins`](wins::Wins) collection type for accumulating game outcomes
+//! - **[`head_to_head`]** - [`HeadToHead`](head_to_head::HeadToHead), every pair of players'
+//!   record against each other
 //! - **[`heads_up`]** - Specialized handling for two-player games via [`HeadsUp`](heads_up::HeadsUp)
+//! - **[`hi_lo`]** - [`HiLoWins`](hi_lo::HiLoWins), a high and a low winner for every outcome of
+//!   a split pot game
+//! - **[`icm`]** - Tournament prize equity with the Independent Chip Model, or from simulated
+//!   [`Rankings`](ranking::Rankings)
+//! - **[`player`]** - Typed [`Player`](player::Player) and [`PlayerSet`](player::PlayerSet)
+//!   identifiers, checked instead of silently zero
+//! - **[`query`]** - Filtering a [`Wins`](wins::Wins) down to the outcomes you care about, and
+//!   conditional percentages and results
+//! - **[`ranking`]** - [`Rankings`](ranking::Rankings), whole finishing orders with ties at any place
+//! - **`parallel`** - [Rayon](https://docs.rs/rayon) support for `Wins` and `WinResults`, behind the
+//!   `rayon` feature
 //! - **[`results`]** - The [`WinResults`](results::WinResults) type for calculating percentages from accumulated wins
+//! - **[`schema`]** - The versioned serde format for [`Wins`](wins::Wins) and [`WinResults`](results::WinResults)
+//! - **[`side_pot`]** - [`SidePots`](side_pot::SidePots), a `Wins` per pot for all ins with unequal
+//!   stacks
+//! - **[`tally`]** - Saved tallies, and [`merge`](tally::merge) for adding them together
+//! - **[`validation`]** - [`Wins::validate()`](wins::Wins::validate) for outcomes that don't fit the
+//!   player count
 //! - **[`util`]** - Utility functions for percentage calculations and other helpers
 //!
 //! ## Quick Start
@@ -101,18 +127,23 @@
 //!
 //! ## Features
 //!
-//! - **Efficient storage** - Uses bit flags to represent winners compactly
+//! - **Efficient storage** - Uses bit flags to represent winners compactly, and [`Wins`](wins::Wins)
+//!   keeps a count per distinct flag pattern rather than one entry per outcome
 //! - **Tie support** - First-class support for ties between any number of players
 //! - **Flexible reporting** - Calculate percentages with or without ties
-//! - **Up to 16 players** - Support for games with many participants
-//! - **Serde support** - Serialize and deserialize results
+//! - **Up to 128 players** - `u16` flags by default, with `u8`, `u32`, `u64` and `u128` available
+//! - **Serde support** - Serialize and deserialize `Wins`, `WinResults` and `HeadsUp`, with a
+//!   [versioned schema](schema)
+//! - **Rayon support** - Collect `Wins` from parallel iterators with the `rayon` feature
 //! - **WASM compatible** - Works in WebAssembly environments (file I/O functions excluded)
 //!
 //! ## Technical Notes
 //!
-//! The [`PlayerFlag`] type is currently a type alias for `u16`. There are plans to refactor
-//! this into a newtype wrapper (e.g., `struct PlayerFlag(u16)`) for better type safety and
-//! to enable implementing methods directly on the type.
+//! The [`PlayerFlag`] type is currently a type alias for `u16`, which is the default width for
+//! [`Wins`](wins::Wins). Use `Wins::<u64>::new()` or any other [`Flag`] width for
+//! games with more than 16 players. [`Player`](player::Player) and
+//! [`PlayerSet`](player::PlayerSet) wrap a flag for when you'd rather have the type safety, and
+//! convert to and from the raw flags the rest of the crate uses.
 //!
 //! ### WASM Compatibility
 //!
@@ -122,32 +153,58 @@
 
 #![warn(clippy::pedantic, clippy::unwrap_used, clippy::expect_used)]
 
+pub mod binary;
+pub mod concurrent;
+pub mod confidence;
+pub mod convergence;
+pub mod csv;
+pub mod ev;
+pub mod flag;
+pub mod head_to_head;
 pub mod heads_up;
+pub mod hi_lo;
+pub mod icm;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod player;
+pub mod query;
+pub mod ranking;
 pub mod results;
+pub mod schema;
+pub mod side_pot;
+pub mod tally;
 pub mod util;
+pub mod validation;
 pub mod win;
 pub mod wins;
+
+use crate::flag::Flag;
+
+/// The default flag width, good for up to 16 players. See [`Flag`] for wider games.
 ///
 /// TODO RF: Refactor this as a `struct PlayerFlag(u16)`.
 ///
 /// NOTE: In retrospect, I should never do these fracking type aliases. I always
 /// regret it. Just wrap it.
+///
+/// UPDATE: Wrapped, sort of. Changing the alias would break everyone, so the wrappers are
+/// [`Player`](player::Player) and [`PlayerSet`](player::PlayerSet) instead.
 pub type PlayerFlag = u16;
 
-pub trait WinResult {
+pub trait WinResult: Sized {
     #[must_use]
     fn is_tie(&self) -> bool;
 
     #[must_use]
-    fn win_for(&self, count: PlayerFlag) -> bool;
+    fn win_for(&self, count: Self) -> bool;
 }
 
-impl WinResult for PlayerFlag {
+impl<F: Flag> WinResult for F {
     fn is_tie(&self) -> bool {
-        self.count_ones() > 1
+        self.players() > 1
     }
 
-    fn win_for(&self, count: PlayerFlag) -> bool {
-        self & count == count
+    fn win_for(&self, count: F) -> bool {
+        *self & count == count
     }
 }
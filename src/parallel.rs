@@ -0,0 +1,188 @@
+//! [Rayon](https://docs.rs/rayon) support, behind the `rayon` feature.
+//!
+//! `Wins` can be collected straight out of a parallel iterator, so a `par_iter()` over a huge
+//! deck enumeration doesn't have to go through a `Vec` or a lock first. Each rayon job tallies
+//! its own `Wins`, and they're combined the same way as `Wins::extend()`.
+//!
+//! ```
+//! use rayon::prelude::*;
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let wins: Wins = (0..1_000_u32)
+//!     .into_par_iter()
+//!     .map(|i| match i % 4 {
+//!         0 | 1 => Win::FIRST,
+//!         2 => Win::SECOND,
+//!         _ => Win::FIRST | Win::SECOND,
+//!     })
+//!     .collect();
+//!
+//! assert_eq!((500, 250), wins.par_results(2).wins_and_ties(0));
+//! ```
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::wins::Wins;
+use rayon::iter::{
+    FromParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelExtend,
+    ParallelIterator,
+};
+
+impl<F: Flag + Send + Sync> FromParallelIterator<F> for Wins<F> {
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = F>,
+    {
+        par_iter
+            .into_par_iter()
+            .fold(Wins::new, |mut wins, count| {
+                wins.add(count);
+                wins
+            })
+            .reduce(Wins::new, |mut a, b| {
+                a.extend(&b);
+                a
+            })
+    }
+}
+
+impl<F: Flag + Send + Sync> FromParallelIterator<Wins<F>> for Wins<F> {
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = Wins<F>>,
+    {
+        par_iter.into_par_iter().reduce(Wins::new, |mut a, b| {
+            a.extend(&b);
+            a
+        })
+    }
+}
+
+impl<F: Flag + Send + Sync> ParallelExtend<F> for Wins<F> {
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = F>,
+    {
+        let wins: Wins<F> = par_iter.into_par_iter().collect();
+        self.extend(&wins);
+    }
+}
+
+impl<F: Flag + Send + Sync> ParallelExtend<Wins<F>> for Wins<F> {
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = Wins<F>>,
+    {
+        let wins: Wins<F> = par_iter.into_par_iter().collect();
+        self.extend(&wins);
+    }
+}
+
+impl<F: Flag + Send + Sync> Wins<F> {
+    /// Parallel version of [`Wins::results()`].
+    #[must_use]
+    pub fn par_results(&self, player_count: usize) -> WinResults {
+        WinResults::par_from_wins(self, player_count)
+    }
+}
+
+impl WinResults {
+    /// Parallel version of [`WinResults::from_wins()`]. The distinct `PlayerFlag` patterns are
+    /// split across rayon's thread pool, and the partial results are added together at the end.
+    #[must_use]
+    pub fn par_from_wins<F: Flag + Send + Sync>(wins: &Wins<F>, player_count: usize) -> Self {
+        let mut results = wins
+            .get()
+            .par_iter()
+            .fold(
+                || WinResults::empty(player_count),
+                |mut results, (count, x)| {
+                    results.record(*count, *x);
+                    results
+                },
+            )
+            .reduce(
+                || WinResults::empty(player_count),
+                |mut a, b| {
+                    a.merge(b);
+                    a
+                },
+            );
+        results.record_no_winner(wins.no_winner());
+        results
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod util__wincounter__parallel__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn outcome(i: usize) -> u64 {
+        match i % 7 {
+            0..=2 => 1,
+            3 => 1 << 40,
+            4 => 1 | 1 << 40,
+            5 => 1 | 2 | 1 << 40,
+            _ => 2,
+        }
+    }
+
+    #[test]
+    fn from_par_iter() {
+        let wins: Wins<u64> = (0..70_000).into_par_iter().map(outcome).collect();
+        let expected: Wins<u64> = (0..70_000).map(outcome).collect();
+
+        assert_eq!(expected, wins);
+    }
+
+    #[test]
+    fn from_par_iter__wins() {
+        let wins: Wins = (0..10)
+            .into_par_iter()
+            .map(|_| Wins::from(vec![Win::FIRST, Win::SECOND | Win::THIRD]))
+            .collect();
+
+        assert_eq!(20, wins.len());
+        assert_eq!(10, wins.count(Win::SECOND | Win::THIRD));
+    }
+
+    #[test]
+    fn par_extend() {
+        let mut wins = Wins::from(vec![Win::FIRST, Win::SECOND]);
+        let mut expected = wins.clone();
+
+        wins.par_extend(vec![Win::FIRST; 1_000]);
+        expected.extend(&Wins::from(vec![Win::FIRST; 1_000]));
+
+        assert_eq!(expected, wins);
+        assert_eq!(1_001, wins.count(Win::FIRST));
+    }
+
+    #[test]
+    fn par_extend__wins() {
+        let mut wins = Wins::from(vec![Win::FIRST]);
+
+        wins.par_extend(vec![Wins::from(vec![Win::FIRST]); 5]);
+
+        assert_eq!(6, wins.count(Win::FIRST));
+    }
+
+    #[test]
+    fn par_from_wins() {
+        let mut wins: Wins<u64> = (0..70_000).map(outcome).collect();
+        wins.add_no_winner_x(7);
+
+        assert_eq!(
+            WinResults::from_wins(&wins, 41),
+            WinResults::par_from_wins(&wins, 41)
+        );
+        assert_eq!(wins.results(2), wins.par_results(2));
+        assert_eq!(7, wins.par_results(2).no_winner);
+        assert_eq!(
+            WinResults::from_wins(&Wins::default(), 3),
+            WinResults::par_from_wins(&Wins::default(), 3)
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! Typed players, so I can stop juggling `usize` indexes and raw flag bits.
+//!
+//! [`Win::from_index()`](crate::win::Win::from_index) quietly hands back an empty flag for a
+//! player it can't represent, and every `Wins` caller ends up doing its own `|` and `&` on
+//! `PlayerFlag`s. A [`Player`] is exactly one player that's known to fit in its flag, and a
+//! [`PlayerSet`] is any group of them, with the set operations spelled out. Anything that
+//! doesn't fit comes back as a [`PlayerError`] instead of a zero.
+//!
+//! ```
+//! use wincounter::player::{Player, PlayerError, PlayerSet};
+//! use wincounter::win::Win;
+//!
+//! let first = Player::new(0).unwrap();
+//! let third = Player::new(2).unwrap();
+//! let tie = PlayerSet::from(first) | PlayerSet::from(third);
+//!
+//! assert_eq!(Win::FIRST | Win::THIRD, tie.flag());
+//! assert!(tie.contains(third));
+//! assert_eq!(vec![0, 2], tie.iter().map(Player::index).collect::<Vec<_>>());
+//! assert_eq!(
+//!     Err(PlayerError::IndexOutOfRange { index: 16, capacity: 16 }),
+//!     Player::<u16>::new(16)
+//! );
+//! ```
+use crate::flag::{Flag, Indexes};
+use crate::PlayerFlag;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitOr};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlayerError {
+    /// The zero based `index` doesn't fit in a flag that holds `capacity` players.
+    IndexOutOfRange { index: usize, capacity: usize },
+    /// A flag that was supposed to be one player had `players` set in it.
+    NotOnePlayer { players: usize },
+    /// The set has a player at `index`, but the game only has `player_count` players.
+    PlayerOutOfRange { index: usize, player_count: usize },
+}
+
+impl Display for PlayerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerError::IndexOutOfRange { index, capacity } => write!(
+                f,
+                "player index {index} is out of range, the flag only holds {capacity} players"
+            ),
+            PlayerError::NotOnePlayer { players } => {
+                write!(f, "expected a flag with one player, found {players}")
+            },
+            PlayerError::PlayerOutOfRange {
+                index,
+                player_count,
+            } => write!(
+                f,
+                "player #{} is out of range, there are only {player_count} players",
+                index + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+/// One player, stored as its single bit flag.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Player<F: Flag = PlayerFlag>(F);
+
+impl<F: Flag> Player<F> {
+    /// The player at a zero based index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlayerError::IndexOutOfRange` if the index doesn't fit in `F`.
+    pub fn new(index: usize) -> Result<Self, PlayerError> {
+        F::from_index(index)
+            .map(Player)
+            .ok_or(PlayerError::IndexOutOfRange {
+                index,
+                capacity: F::CAPACITY,
+            })
+    }
+
+    /// The zero based index of the player.
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0.lowest_index().unwrap_or_default()
+    }
+
+    /// Only a flag with exactly one player set is a `Player`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlayerError::NotOnePlayer` if the flag is empty or a tie.
+    pub fn from_flag(flag: F) -> Result<Self, PlayerError> {
+        match flag.players() {
+            1 => Ok(Player(flag)),
+            players => Err(PlayerError::NotOnePlayer { players }),
+        }
+    }
+
+    #[must_use]
+    pub fn flag(self) -> F {
+        self.0
+    }
+}
+
+/// Counts from one, the same way `WinResults` does: `Player #1`.
+impl<F: Flag> Display for Player<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Player #{}", self.index() + 1)
+    }
+}
+
+/// Any group of players, including nobody. Every flag is a valid set, so converting from one
+/// can't fail, but [`PlayerSet::checked()`] will make sure it fits the game.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PlayerSet<F: Flag = PlayerFlag>(F);
+
+impl<F: Flag> PlayerSet<F> {
+    pub const EMPTY: Self = PlayerSet(F::NONE);
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Makes sure none of the players in the flag are past the end of a `player_count` game.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlayerError::PlayerOutOfRange` for the first player that doesn't fit.
+    pub fn checked(flag: F, player_count: usize) -> Result<Self, PlayerError> {
+        match flag.indexes().find(|index| *index >= player_count) {
+            Some(index) => Err(PlayerError::PlayerOutOfRange {
+                index,
+                player_count,
+            }),
+            None => Ok(PlayerSet(flag)),
+        }
+    }
+
+    /// The set of players at the zero based indexes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PlayerError::IndexOutOfRange` for the first index that doesn't fit in `F`.
+    pub fn from_indexes<I: IntoIterator<Item = usize>>(indexes: I) -> Result<Self, PlayerError> {
+        indexes.into_iter().map(Player::new).collect()
+    }
+
+    #[must_use]
+    pub fn flag(self) -> F {
+        self.0
+    }
+
+    #[must_use]
+    pub fn len(self) -> usize {
+        self.0.players()
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0.is_none()
+    }
+
+    /// More than one player, which as an outcome is a tie.
+    #[must_use]
+    pub fn is_tie(self) -> bool {
+        self.len() > 1
+    }
+
+    #[must_use]
+    pub fn contains(self, player: Player<F>) -> bool {
+        self.0 & player.0 != F::NONE
+    }
+
+    /// Adds the player, returning false if they were already in the set.
+    pub fn insert(&mut self, player: Player<F>) -> bool {
+        let added = !self.contains(player);
+        self.0 |= player.0;
+        added
+    }
+
+    /// Takes the player out, returning false if they weren't in the set.
+    pub fn remove(&mut self, player: Player<F>) -> bool {
+        let removed = self.contains(player);
+        self.0 &= !player.0;
+        removed
+    }
+
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        PlayerSet(self.0 | other.0)
+    }
+
+    #[must_use]
+    pub fn intersection(self, other: Self) -> Self {
+        PlayerSet(self.0 & other.0)
+    }
+
+    #[must_use]
+    pub fn difference(self, other: Self) -> Self {
+        PlayerSet(self.0 & !other.0)
+    }
+
+    #[must_use]
+    pub fn is_subset(self, other: Self) -> bool {
+        self.0 & other.0 == self.0
+    }
+
+    #[must_use]
+    pub fn is_superset(self, other: Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// The players in the set, lowest index first.
+    #[must_use]
+    pub fn iter(self) -> Players<F> {
+        Players(self.0.indexes())
+    }
+}
+
+impl<F: Flag> From<F> for PlayerSet<F> {
+    fn from(flag: F) -> Self {
+        PlayerSet(flag)
+    }
+}
+
+impl<F: Flag> From<Player<F>> for PlayerSet<F> {
+    fn from(player: Player<F>) -> Self {
+        PlayerSet(player.0)
+    }
+}
+
+impl<F: Flag> FromIterator<Player<F>> for PlayerSet<F> {
+    fn from_iter<I: IntoIterator<Item = Player<F>>>(iter: I) -> Self {
+        let mut set = PlayerSet::new();
+        for player in iter {
+            set.insert(player);
+        }
+        set
+    }
+}
+
+impl<F: Flag> IntoIterator for PlayerSet<F> {
+    type Item = Player<F>;
+    type IntoIter = Players<F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<F: Flag> BitOr for PlayerSet<F> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<F: Flag> BitAnd for PlayerSet<F> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+/// The raw flag conversions. These can't be written once for every `Flag` because of the blanket
+/// `TryFrom` and the orphan rules, so they're stamped out per width like the `Flag` impls are.
+macro_rules! impl_flag_conversions {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for Player<$t> {
+                type Error = PlayerError;
+
+                fn try_from(flag: $t) -> Result<Self, Self::Error> {
+                    Player::from_flag(flag)
+                }
+            }
+
+            impl From<Player<$t>> for $t {
+                fn from(player: Player<$t>) -> Self {
+                    player.flag()
+                }
+            }
+
+            impl From<PlayerSet<$t>> for $t {
+                fn from(players: PlayerSet<$t>) -> Self {
+                    players.flag()
+                }
+            }
+        )*
+    };
+}
+
+impl_flag_conversions!(u8, u16, u32, u64, u128);
+
+/// `Player #1, Player #3`, or `nobody` for an empty set.
+impl<F: Flag> Display for PlayerSet<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "nobody");
+        }
+        let players: Vec<String> = self.iter().map(|player| player.to_string()).collect();
+        write!(f, "{}", players.join(", "))
+    }
+}
+
+/// Iterator over the players in a `PlayerSet`. See `PlayerSet::iter()`.
+#[derive(Clone, Debug)]
+pub struct Players<F: Flag>(Indexes<F>);
+
+impl<F: Flag> Iterator for Players<F> {
+    type Item = Player<F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .and_then(|index| F::from_index(index))
+            .map(Player)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<F: Flag> ExactSizeIterator for Players<F> {}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__player__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn set(indexes: &[usize]) -> PlayerSet {
+        PlayerSet::from_indexes(indexes.iter().copied()).unwrap()
+    }
+
+    #[test]
+    fn player__new() {
+        assert_eq!(Win::SIXTEENTH, Player::<u16>::new(15).unwrap().flag());
+        assert_eq!(99, Player::<u128>::new(99).unwrap().index());
+        assert_eq!(
+            "player index 8 is out of range, the flag only holds 8 players",
+            Player::<u8>::new(8).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn player__try_from() {
+        assert_eq!(2, Player::try_from(Win::THIRD).unwrap().index());
+        assert_eq!(Win::THIRD, PlayerFlag::from(Player::<u16>::new(2).unwrap()));
+        assert_eq!(Ok(Player(1_u64 << 40)), Player::from_flag(1_u64 << 40));
+        assert_eq!(
+            Err(PlayerError::NotOnePlayer { players: 0 }),
+            Player::try_from(0_u16)
+        );
+        assert_eq!(
+            "expected a flag with one player, found 2",
+            Player::try_from(Win::FIRST | Win::SECOND)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn player__display() {
+        assert_eq!("Player #3", Player::<u16>::new(2).unwrap().to_string());
+    }
+
+    #[test]
+    fn checked() {
+        assert_eq!(
+            Win::FIRST | Win::THIRD,
+            PlayerSet::checked(Win::FIRST | Win::THIRD, 3)
+                .unwrap()
+                .flag()
+        );
+        assert_eq!(
+            "player #3 is out of range, there are only 2 players",
+            PlayerSet::checked(Win::FIRST | Win::THIRD, 2)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn from_indexes() {
+        assert_eq!(Win::SECOND | Win::FORTH, set(&[1, 3]).flag());
+        assert_eq!(Win::SECOND | Win::FORTH, PlayerFlag::from(set(&[1, 3])));
+        assert_eq!(
+            Err(PlayerError::IndexOutOfRange {
+                index: 16,
+                capacity: 16
+            }),
+            PlayerSet::<u16>::from_indexes([0, 16])
+        );
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut players = PlayerSet::<u8>::new();
+        let second = Player::new(1).unwrap();
+
+        assert!(players.insert(second));
+        assert!(!players.insert(second));
+        assert_eq!(1, players.len());
+        assert!(players.remove(second));
+        assert!(!players.remove(second));
+        assert!(players.is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[0, 1]);
+        let b = set(&[1, 2]);
+
+        assert_eq!(set(&[0, 1, 2]), a.union(b));
+        assert_eq!(set(&[0, 1, 2]), a | b);
+        assert_eq!(set(&[1]), a.intersection(b));
+        assert_eq!(set(&[1]), a & b);
+        assert_eq!(set(&[0]), a.difference(b));
+        assert!(set(&[1]).is_subset(a));
+        assert!(!a.is_subset(b));
+        assert!(PlayerSet::EMPTY.is_subset(a));
+        assert!(a.is_superset(set(&[0])));
+        assert!(a.is_tie());
+        assert!(!set(&[0]).is_tie());
+    }
+
+    #[test]
+    fn iter() {
+        let players = PlayerSet::<u128>::from(1 | 1 << 64 | 1 << 127);
+
+        assert_eq!(3, players.iter().len());
+        assert_eq!(
+            vec![0, 64, 127],
+            players.into_iter().map(Player::index).collect::<Vec<_>>()
+        );
+        assert_eq!(players, players.iter().collect());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("Player #1, Player #3", set(&[0, 2]).to_string());
+        assert_eq!("nobody", PlayerSet::<u16>::new().to_string());
+    }
+}
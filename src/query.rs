@@ -0,0 +1,366 @@
+//! Asking a `Wins` questions about some of its outcomes instead of all of them.
+//!
+//! "How often does the third player win when the first one doesn't?" is a question the flag
+//! patterns can already answer. Throw away every outcome where the first player won, and what's
+//! left is a smaller `Wins` with the third player's share of it sitting right there. That's all
+//! this module does. [`Wins::filter()`] keeps the outcomes a predicate likes, and
+//! [`Wins::results_given()`] turns them into a `WinResults`, so every percentage in it is a
+//! conditional one.
+//!
+//! A predicate is either a closure over the flag, or a [`Condition`], which is built on
+//! [`WinResult::win_for()`] and [`WinResult::is_tie()`] and can be combined with `!`, `&` and
+//! `|`.
+//!
+//! ```
+//! use wincounter::query::Condition;
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let mut wins = Wins::default();
+//! wins.add_x(Win::FIRST, 50);
+//! wins.add_x(Win::SECOND, 20);
+//! wins.add_x(Win::THIRD, 20);
+//! wins.add_x(Win::FIRST | Win::THIRD, 10);
+//!
+//! let third_wins = Condition::WinFor(Win::THIRD);
+//! let first_loses = !Condition::WinFor(Win::FIRST);
+//!
+//! // Player 3 wins half of the 40 outcomes player 1 doesn't.
+//! assert_eq!(50.0, wins.percentage_given(&third_wins, &first_loses));
+//! assert_eq!(40, wins.count_where(&first_loses));
+//!
+//! let results = wins.results_given(&first_loses, 3);
+//! assert_eq!(40, results.case_count);
+//! assert_eq!((20, 0), results.wins_and_ties(2));
+//!
+//! // Closures work too.
+//! assert_eq!(10, wins.count_where(&|count: u16| count == Win::FIRST | Win::THIRD));
+//! ```
+//!
+//! Outcomes that nobody won are checked as an empty flag, so they're kept by
+//! `!Condition::WinFor(Win::FIRST)` and `Condition::NoWinner`, and dropped by just about
+//! everything else. A `0` pattern added as if it were a win isn't one of them. It's the kind
+//! of thing [`Wins::validate()`] complains about, and no predicate gets to keep it.
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::util::Util;
+use crate::wins::Wins;
+use crate::{PlayerFlag, WinResult};
+use std::ops::{BitAnd, BitOr, Not};
+
+/// Anything that can say yes or no to an outcome's flag.
+pub trait Predicate<F: Flag> {
+    fn matches(&self, count: F) -> bool;
+}
+
+impl<F: Flag, P: Fn(F) -> bool> Predicate<F> for P {
+    fn matches(&self, count: F) -> bool {
+        self(count)
+    }
+}
+
+/// The usual questions, so you don't have to write the closures yourself.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Condition<F: Flag = PlayerFlag> {
+    /// Every outcome.
+    All,
+    /// Every player in the flag won, alone or as part of a tie. Same as `WinResult::win_for()`.
+    WinFor(F),
+    /// At least one of the players in the flag won, or tied.
+    Involves(F),
+    /// More than one player won.
+    Tie,
+    /// Nobody won. Only the outcomes counted by [`Wins::no_winner()`] get here.
+    NoWinner,
+    Not(Box<Condition<F>>),
+    And(Box<Condition<F>>, Box<Condition<F>>),
+    Or(Box<Condition<F>>, Box<Condition<F>>),
+}
+
+impl<F: Flag> Predicate<F> for Condition<F> {
+    fn matches(&self, count: F) -> bool {
+        match self {
+            Condition::All => true,
+            Condition::WinFor(players) => count.win_for(*players),
+            Condition::Involves(players) => !(count & *players).is_none(),
+            Condition::Tie => count.is_tie(),
+            Condition::NoWinner => count.is_none(),
+            Condition::Not(condition) => !condition.matches(count),
+            Condition::And(a, b) => a.matches(count) && b.matches(count),
+            Condition::Or(a, b) => a.matches(count) || b.matches(count),
+        }
+    }
+}
+
+impl<F: Flag> Not for Condition<F> {
+    type Output = Condition<F>;
+
+    fn not(self) -> Self::Output {
+        Condition::Not(Box::new(self))
+    }
+}
+
+impl<F: Flag> BitAnd for Condition<F> {
+    type Output = Condition<F>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Condition::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Flag> BitOr for Condition<F> {
+    type Output = Condition<F>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Condition::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Flag> Wins<F> {
+    /// A new `Wins` with only the outcomes that match, no-winners included if the predicate
+    /// is happy with an empty flag. `0` patterns are always left out.
+    #[must_use]
+    pub fn filter<P: Predicate<F>>(&self, predicate: &P) -> Wins<F> {
+        let mut filtered = Wins::new();
+        for (count, x) in self.matching_patterns(predicate) {
+            filtered.add_x(count, x);
+        }
+        if predicate.matches(F::NONE) {
+            filtered.add_no_winner_x(self.no_winner());
+        }
+        filtered
+    }
+
+    /// How many outcomes match, counted the same way as [`filter()`](Wins::filter).
+    #[must_use]
+    pub fn count_where<P: Predicate<F>>(&self, predicate: &P) -> usize {
+        let patterns: usize = self.matching_patterns(predicate).map(|(_, x)| x).sum();
+        if predicate.matches(F::NONE) {
+            patterns + self.no_winner()
+        } else {
+            patterns
+        }
+    }
+
+    /// The percentage of all outcomes that match, out of the ones
+    /// [`count_where()`](Wins::count_where) can count, so `0` patterns are left out of both
+    /// sides and [`Condition::All`] is always 100%.
+    #[must_use]
+    pub fn percentage_where<P: Predicate<F>>(&self, predicate: &P) -> f32 {
+        Util::calculate_percentage(
+            self.count_where(predicate),
+            self.len() - self.count(F::NONE),
+        )
+    }
+
+    /// The percentage of the outcomes matching `given` that also match `event`. Zero if nothing
+    /// matches `given`, the same as every other percentage in the crate.
+    #[must_use]
+    pub fn percentage_given<E, G>(&self, event: &E, given: &G) -> f32
+    where
+        E: Predicate<F>,
+        G: Predicate<F>,
+    {
+        self.filter(given).percentage_where(event)
+    }
+
+    /// `WinResults` for only the outcomes matching `given`, so the case count is how many of
+    /// them there were, and every percentage is conditional on `given`.
+    #[must_use]
+    pub fn results_given<G: Predicate<F>>(&self, given: &G, player_count: usize) -> WinResults {
+        WinResults::from_wins(&self.filter(given), player_count)
+    }
+
+    /// The patterns that match, skipping `0` patterns so that only the real no-winners are
+    /// checked as an empty flag.
+    fn matching_patterns<'a, P: Predicate<F>>(
+        &'a self,
+        predicate: &'a P,
+    ) -> impl Iterator<Item = (F, usize)> + 'a {
+        self.iter()
+            .filter(move |(count, _)| !count.is_none() && predicate.matches(*count))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp)]
+mod util__wincounter__query__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn three_way() -> Wins {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 50);
+        wins.add_x(Win::SECOND, 20);
+        wins.add_x(Win::THIRD, 20);
+        wins.add_x(Win::FIRST | Win::THIRD, 10);
+        wins.add_no_winner_x(4);
+        wins
+    }
+
+    #[test]
+    fn condition__matches() {
+        let tie = Win::FIRST | Win::THIRD;
+
+        assert!(Condition::<PlayerFlag>::All.matches(0));
+        assert!(Condition::WinFor(Win::FIRST).matches(tie));
+        assert!(!Condition::WinFor(Win::FIRST | Win::SECOND).matches(tie));
+        assert!(Condition::Involves(Win::FIRST | Win::SECOND).matches(tie));
+        assert!(!Condition::Involves(Win::SECOND).matches(tie));
+        assert!(Condition::Tie.matches(tie));
+        assert!(!Condition::Tie.matches(Win::FIRST));
+        assert!(Condition::<PlayerFlag>::NoWinner.matches(0));
+        assert!(!Condition::NoWinner.matches(Win::FIRST));
+    }
+
+    #[test]
+    fn condition__operators() {
+        let first = Condition::WinFor(Win::FIRST);
+        let third = Condition::WinFor(Win::THIRD);
+
+        assert!((!first.clone()).matches(Win::SECOND));
+        assert!((first.clone() & third.clone()).matches(Win::FIRST | Win::THIRD));
+        assert!(!(first.clone() & third.clone()).matches(Win::FIRST));
+        assert!((first.clone() | third.clone()).matches(Win::THIRD));
+        assert!(!(first | third).matches(Win::SECOND));
+    }
+
+    #[test]
+    fn filter() {
+        let wins = three_way();
+
+        let filtered = wins.filter(&Condition::WinFor(Win::THIRD));
+
+        assert_eq!(30, filtered.len());
+        assert_eq!(0, filtered.no_winner());
+        assert_eq!(10, filtered.count(Win::FIRST | Win::THIRD));
+        assert_eq!(0, filtered.count(Win::FIRST));
+    }
+
+    #[test]
+    fn filter__keeps_no_winners() {
+        let wins = three_way();
+
+        let filtered = wins.filter(&!Condition::WinFor(Win::FIRST));
+
+        assert_eq!(44, filtered.len());
+        assert_eq!(4, filtered.no_winner());
+    }
+
+    #[test]
+    fn filter__closure() {
+        let wins = three_way();
+
+        let filtered = wins.filter(&|count: PlayerFlag| count.players() == 1);
+
+        assert_eq!(90, filtered.len());
+        assert_eq!(0, filtered.no_winner());
+    }
+
+    #[test]
+    fn count_where() {
+        let wins = three_way();
+
+        assert_eq!(104, wins.count_where(&Condition::All));
+        assert_eq!(10, wins.count_where(&Condition::Tie));
+        assert_eq!(4, wins.count_where(&Condition::NoWinner));
+        assert_eq!(0, Wins::default().count_where(&Condition::All));
+    }
+
+    #[test]
+    fn count_where__zero_patterns() {
+        let mut wins = three_way();
+        wins.add_x(0, 3);
+
+        assert_eq!(4, wins.count_where(&Condition::NoWinner));
+        assert_eq!(4, wins.filter(&Condition::NoWinner).len());
+        assert_eq!(0, wins.filter(&Condition::All).count(0));
+        assert_eq!(104, wins.count_where(&Condition::All));
+        assert_eq!(107, wins.len());
+    }
+
+    #[test]
+    fn percentage_where() {
+        let wins = Wins::from(vec![
+            Win::FIRST,
+            Win::SECOND,
+            Win::FIRST | Win::SECOND,
+            Win::THIRD,
+        ]);
+
+        assert_eq!(100.0, wins.percentage_where(&Condition::All));
+        assert_eq!(
+            50.0,
+            wins.percentage_where(&Condition::Involves(Win::SECOND | Win::NINTH))
+        );
+        assert_eq!(25.0, wins.percentage_where(&Condition::Tie));
+        assert_eq!(0.0, Wins::default().percentage_where(&Condition::All));
+    }
+
+    #[test]
+    fn percentage_given() {
+        let wins = three_way();
+        let third = Condition::WinFor(Win::THIRD);
+
+        // 20 of the 44 outcomes player 1 didn't win.
+        assert_eq!(
+            Util::calculate_percentage(20, 44),
+            wins.percentage_given(&third, &!Condition::WinFor(Win::FIRST))
+        );
+        assert_eq!(
+            100.0,
+            wins.percentage_given(
+                &Condition::Tie,
+                &(third.clone() & Condition::Involves(Win::FIRST))
+            )
+        );
+        assert_eq!(
+            0.0,
+            wins.percentage_given(&third, &Condition::WinFor(Win::NINTH))
+        );
+    }
+
+    #[test]
+    fn results_given() {
+        let wins = three_way();
+
+        let results = wins.results_given(&Condition::Involves(Win::THIRD), 3);
+
+        assert_eq!(30, results.case_count);
+        assert_eq!(0, results.no_winner);
+        assert_eq!((0, 10), results.wins_and_ties(0));
+        assert_eq!((0, 0), results.wins_and_ties(1));
+        assert_eq!((20, 10), results.wins_and_ties(2));
+        assert_eq!(100.0, results.wins_total_percentage(2));
+    }
+
+    #[test]
+    fn results_given__all() {
+        let wins = three_way();
+
+        assert_eq!(wins.results(3), wins.results_given(&Condition::All, 3));
+    }
+
+    #[test]
+    fn percentage_where__all() {
+        let mut wins = three_way();
+        wins.add_x(0, 10);
+        wins.add_no_winner_x(5);
+
+        assert_eq!(100.0, wins.percentage_where(&Condition::All));
+        assert_eq!(0.0, Wins::<u8>::new().percentage_where(&Condition::All));
+    }
+
+    #[test]
+    fn wider_flags() {
+        let mut wins: Wins<u128> = Wins::new();
+        wins.add_x(1 << 100, 3);
+        wins.add_x(1 << 100 | 1, 1);
+        wins.add(1);
+
+        assert_eq!(
+            75.0,
+            wins.percentage_given(&!Condition::WinFor(1), &Condition::WinFor(1 << 100))
+        );
+    }
+}
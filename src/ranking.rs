@@ -0,0 +1,517 @@
+//! Finishing orders, for when who came second matters too.
+//!
+//! `Wins` only cares about who came first. Tournaments, races and battle royales pay out, or at
+//! least brag, further down the order than that. [`Rankings`] records the whole finishing
+//! order of every outcome, as a list of flags from first place down, where each flag is
+//! everyone who finished in that spot. More than one player in a flag is a tie for that place.
+//!
+//! Places work the way they do in sports: if two players tie for first, the next one is third.
+//! [`RankingResults`] gives every player how often they finished in each place, and their
+//! average place.
+//!
+//! ```
+//! use wincounter::ranking::Rankings;
+//! use wincounter::win::Win;
+//!
+//! let mut rankings = Rankings::default();
+//! rankings.add(&[Win::FIRST, Win::SECOND, Win::THIRD]).unwrap();
+//! rankings.add(&[Win::SECOND, Win::THIRD, Win::FIRST]).unwrap();
+//! rankings.add(&[Win::FIRST | Win::SECOND, Win::THIRD]).unwrap();
+//!
+//! // A player can't finish twice.
+//! assert!(rankings.add(&[Win::FIRST, Win::FIRST]).is_err());
+//!
+//! let results = rankings.results(3);
+//!
+//! assert_eq!(2, results.place_count(0, 1));
+//! assert_eq!(1, results.place_count(0, 3));
+//! assert_eq!(Some(5.0 / 3.0), results.average_place(0));
+//! assert_eq!(Some(8.0 / 3.0), results.average_place(2));
+//!
+//! // The winners are just a plain `Wins`.
+//! assert_eq!((2, 1), rankings.wins().wins_for(Win::FIRST));
+//! ```
+use crate::flag::Flag;
+use crate::results::label_or;
+use crate::util::Util;
+use crate::wins::Wins;
+use crate::{PlayerFlag, WinResult};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Something wrong with a finishing order. Places are one based, and players are widened to
+/// `u128` so the error doesn't need the flag type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RankingError {
+    /// Nobody finished in the place.
+    EmptyPlace { place: usize },
+    /// The players finished in an earlier place as well.
+    FinishedTwice { place: usize, players: u128 },
+}
+
+impl Display for RankingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RankingError::EmptyPlace { place } => write!(f, "nobody finished {}", ordinal(*place)),
+            RankingError::FinishedTwice { place, players } => {
+                write!(
+                    f,
+                    "players {players:#b} finished {} and earlier",
+                    ordinal(*place)
+                )
+            },
+        }
+    }
+}
+
+impl std::error::Error for RankingError {}
+
+/// A count for every distinct finishing order, the same way `Wins` counts single flags.
+/// `Default` is only for the `u16` version, like `Wins`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rankings<F: Flag = PlayerFlag> {
+    counts: BTreeMap<Vec<F>, usize>,
+}
+
+impl<F: Flag> Rankings<F> {
+    #[must_use]
+    pub fn new() -> Self {
+        Rankings {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a finishing order, first place first. Players who aren't in it didn't finish, and
+    /// an empty order is an outcome where nobody did.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RankingError` if a place is empty, or a player is in more than one place.
+    /// Nothing is recorded if so.
+    pub fn add(&mut self, order: &[F]) -> Result<(), RankingError> {
+        self.add_x(order, 1)
+    }
+
+    /// `add()`, x number of times.
+    ///
+    /// # Errors
+    ///
+    /// The same as `add()`.
+    pub fn add_x(&mut self, order: &[F], x: usize) -> Result<(), RankingError> {
+        let mut finished = F::NONE;
+        for (i, group) in order.iter().enumerate() {
+            let place = i + 1;
+            if group.is_none() {
+                return Err(RankingError::EmptyPlace { place });
+            }
+            let twice = *group & finished;
+            if !twice.is_none() {
+                return Err(RankingError::FinishedTwice {
+                    place,
+                    players: twice.to_u128(),
+                });
+            }
+            finished |= *group;
+        }
+        if x > 0 {
+            *self.counts.entry(order.to_vec()).or_default() += x;
+        }
+        Ok(())
+    }
+
+    pub fn extend(&mut self, other: &Rankings<F>) {
+        for (order, x) in &other.counts {
+            *self.counts.entry(order.clone()).or_default() += x;
+        }
+    }
+
+    /// The number of times that exact finishing order was recorded.
+    #[must_use]
+    pub fn count(&self, order: &[F]) -> usize {
+        self.counts.get(order).copied().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Iterates over each distinct finishing order, in order, along with the number of times it
+    /// was recorded.
+    pub fn iter(&self) -> impl Iterator<Item = (&[F], usize)> + '_ {
+        self.counts.iter().map(|(order, x)| (order.as_slice(), *x))
+    }
+
+    /// The total number of outcomes recorded.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Just who came first, as a plain `Wins`. Outcomes where nobody finished are its
+    /// no-winners.
+    #[must_use]
+    pub fn wins(&self) -> Wins<F> {
+        let mut wins = Wins::new();
+        for (order, x) in self.iter() {
+            match order.first() {
+                Some(first) => wins.add_x(*first, x),
+                None => wins.add_no_winner_x(x),
+            }
+        }
+        wins
+    }
+
+    /// Players at or past `player_count` are ignored, though they still take up their places.
+    /// Anybody they push past the last of the `player_count` places counts as unplaced.
+    #[must_use]
+    pub fn results(&self, player_count: usize) -> RankingResults {
+        RankingResults::from_rankings(self, player_count)
+    }
+}
+
+impl Default for Rankings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every player's finishes.
+///
+/// `places[i][p]` is how many times player `i` finished in place `p + 1`, ties included, and
+/// `ties[i][p]` how many of those were shared. `unplaced[i]` is how often they didn't finish at
+/// all, or finished somewhere past the last place there's a column for. Every player's places
+/// and unplaced add up to `case_count`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RankingResults {
+    pub case_count: usize,
+    pub player_count: usize,
+    pub places: Vec<Vec<usize>>,
+    pub ties: Vec<Vec<usize>>,
+    pub unplaced: Vec<usize>,
+    pub labels: Vec<String>,
+}
+
+impl RankingResults {
+    #[must_use]
+    pub fn from_rankings<F: Flag>(rankings: &Rankings<F>, player_count: usize) -> RankingResults {
+        let mut results = RankingResults {
+            player_count,
+            places: vec![vec![0; player_count]; player_count],
+            ties: vec![vec![0; player_count]; player_count],
+            unplaced: vec![0; player_count],
+            ..Default::default()
+        };
+        for (order, x) in rankings.iter() {
+            results.case_count += x;
+            let mut place = 1;
+            let mut finished = F::NONE;
+            for group in order {
+                let tied = group.is_tie();
+                for i in group.indexes().filter(|i| *i < player_count) {
+                    if let Some(count) = results.places[i].get_mut(place - 1) {
+                        *count += x;
+                        if tied {
+                            results.ties[i][place - 1] += x;
+                        }
+                    } else {
+                        results.unplaced[i] += x;
+                    }
+                }
+                place += group.players();
+                finished |= *group;
+            }
+            for i in 0..player_count {
+                if !finished.contains_index(i) {
+                    results.unplaced[i] += x;
+                }
+            }
+        }
+        results
+    }
+
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The label the player was given, falling back to `Player #1` etc.
+    #[must_use]
+    pub fn label(&self, player_index: usize) -> String {
+        label_or(&self.labels, player_index, "Player #")
+    }
+
+    /// How many times the player finished in the place, which starts at `1`, ties included.
+    #[must_use]
+    pub fn place_count(&self, player_index: usize, place: usize) -> usize {
+        self.places
+            .get(player_index)
+            .and_then(|places| places.get(place.checked_sub(1)?))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn place_percentage(&self, player_index: usize, place: usize) -> f32 {
+        Util::calculate_percentage(self.place_count(player_index, place), self.case_count)
+    }
+
+    /// How often the player finished in the top `n` places.
+    #[must_use]
+    pub fn top_percentage(&self, player_index: usize, n: usize) -> f32 {
+        let top = (1..=n)
+            .map(|place| self.place_count(player_index, place))
+            .sum();
+        Util::calculate_percentage(top, self.case_count)
+    }
+
+    /// The player's average place over the outcomes they finished in, or `None` if they never
+    /// finished.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_place(&self, player_index: usize) -> Option<f64> {
+        let places = self.places.get(player_index)?;
+        let finished: usize = places.iter().sum();
+        if finished == 0 {
+            return None;
+        }
+        let total: usize = places.iter().enumerate().map(|(p, x)| (p + 1) * x).sum();
+        Some(total as f64 / finished as f64)
+    }
+
+    /// Player indexes, best average place first. Players who never finished go last, and
+    /// players with the same average stay in index order.
+    #[must_use]
+    pub fn standings(&self) -> Vec<usize> {
+        let mut players: Vec<usize> = (0..self.player_count).collect();
+        players.sort_by(|a, b| {
+            let a = self.average_place(*a).unwrap_or(f64::INFINITY);
+            let b = self.average_place(*b).unwrap_or(f64::INFINITY);
+            a.total_cmp(&b)
+        });
+        players
+    }
+}
+
+/// `1st`, `2nd`, `3rd`, `4th`, and so on, `11th` to `13th` included.
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// One line per player, with their average place and how often they finished in each one.
+///
+/// ```txt
+/// Player #1 1.67 avg [1st 66.67%, 2nd 0.00%, 3rd 33.33%]
+/// ```
+impl Display for RankingResults {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for i in 0..self.player_count {
+            let average = self
+                .average_place(i)
+                .map_or("-".to_string(), |average| format!("{average:.2}"));
+            let places: Vec<String> = (1..=self.player_count)
+                .map(|place| format!("{} {:.2}%", ordinal(place), self.place_percentage(i, place)))
+                .collect();
+            writeln!(f, "{} {average} avg [{}]", self.label(i), places.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp, clippy::unwrap_used)]
+mod util__wincounter__ranking__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn rankings() -> Rankings {
+        let mut rankings = Rankings::default();
+        rankings
+            .add(&[Win::FIRST, Win::SECOND, Win::THIRD])
+            .unwrap();
+        rankings
+            .add(&[Win::SECOND, Win::THIRD, Win::FIRST])
+            .unwrap();
+        rankings
+            .add(&[Win::FIRST | Win::SECOND, Win::THIRD])
+            .unwrap();
+        rankings
+    }
+
+    #[test]
+    fn add() {
+        let rankings = rankings();
+
+        assert_eq!(3, rankings.len());
+        assert_eq!(1, rankings.count(&[Win::FIRST, Win::SECOND, Win::THIRD]));
+        assert_eq!(0, rankings.count(&[Win::THIRD]));
+        assert!(!rankings.is_empty());
+        assert!(Rankings::default().is_empty());
+    }
+
+    #[test]
+    fn add_x() {
+        let mut rankings = Rankings::default();
+
+        rankings.add_x(&[Win::SECOND, Win::FIRST], 3).unwrap();
+        rankings.add_x(&[Win::SECOND], 0).unwrap();
+
+        assert_eq!(3, rankings.len());
+        assert_eq!(1, rankings.iter().count());
+    }
+
+    #[test]
+    fn add__errors() {
+        let mut rankings = rankings();
+
+        assert_eq!(
+            Err(RankingError::EmptyPlace { place: 2 }),
+            rankings.add(&[Win::FIRST, 0, Win::SECOND])
+        );
+        assert_eq!(
+            "players 0b1 finished 3rd and earlier",
+            rankings
+                .add(&[Win::FIRST, Win::SECOND, Win::FIRST | Win::THIRD])
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(3, rankings.len());
+    }
+
+    #[test]
+    fn extend() {
+        let mut rankings = rankings();
+
+        rankings.extend(&rankings.clone());
+
+        assert_eq!(6, rankings.len());
+        assert_eq!(2, rankings.count(&[Win::FIRST | Win::SECOND, Win::THIRD]));
+    }
+
+    #[test]
+    fn wins() {
+        let mut rankings = rankings();
+        rankings.add(&[]).unwrap();
+
+        let wins = rankings.wins();
+
+        assert_eq!(4, wins.len());
+        assert_eq!(1, wins.no_winner());
+        assert_eq!((2, 1), wins.wins_for(Win::SECOND));
+    }
+
+    #[test]
+    fn results() {
+        let results = rankings().results(3);
+
+        assert_eq!(3, results.case_count);
+        assert_eq!(vec![2, 0, 1], results.places[0]);
+        assert_eq!(vec![1, 0, 0], results.ties[0]);
+        assert_eq!(vec![2, 1, 0], results.places[1]);
+        // Tied for first, so the third player is third, not second.
+        assert_eq!(vec![0, 1, 2], results.places[2]);
+        assert_eq!(vec![0, 0, 0], results.unplaced);
+    }
+
+    #[test]
+    fn results__unplaced() {
+        let mut rankings = Rankings::default();
+        rankings.add(&[Win::SECOND]).unwrap();
+        rankings
+            .add(&[Win::FIRST, Win::FIFTH, Win::SECOND])
+            .unwrap();
+
+        let results = rankings.results(3);
+
+        assert_eq!(vec![1, 0, 2], results.unplaced);
+        // The fifth player isn't counted, but still takes second place.
+        assert_eq!(1, results.place_count(1, 3));
+        assert_eq!(None, results.average_place(2));
+    }
+
+    #[test]
+    fn results__pushed_past_last_place() {
+        let mut rankings = Rankings::default();
+        rankings
+            .add(&[Win::FIFTH, Win::FORTH, Win::FIRST | Win::SECOND])
+            .unwrap();
+        rankings.add(&[Win::SECOND, Win::FIRST]).unwrap();
+
+        let results = rankings.results(2);
+
+        assert_eq!(vec![1, 1], results.unplaced);
+        for i in 0..2 {
+            let placed: usize = results.places[i].iter().sum();
+            assert_eq!(results.case_count, placed + results.unplaced[i]);
+        }
+    }
+
+    #[test]
+    fn place_count() {
+        let results = rankings().results(3);
+
+        assert_eq!(2, results.place_count(0, 1));
+        assert_eq!(0, results.place_count(0, 0));
+        assert_eq!(0, results.place_count(0, 4));
+        assert_eq!(0, results.place_count(3, 1));
+    }
+
+    #[test]
+    fn percentages() {
+        let results = rankings().results(3);
+
+        assert_eq!(
+            Util::calculate_percentage(2, 3),
+            results.place_percentage(0, 1)
+        );
+        assert_eq!(100.0, results.top_percentage(1, 2));
+        assert_eq!(100.0, results.top_percentage(2, 3));
+        assert_eq!(0.0, RankingResults::default().place_percentage(0, 1));
+    }
+
+    #[test]
+    fn average_place_and_standings() {
+        let results = rankings().results(3);
+
+        assert_eq!(Some(4.0 / 3.0), results.average_place(1));
+        assert_eq!(None, results.average_place(3));
+        assert_eq!(vec![1, 0, 2], results.standings());
+    }
+
+    #[test]
+    fn ordinal() {
+        assert_eq!("1st", super::ordinal(1));
+        assert_eq!("2nd", super::ordinal(2));
+        assert_eq!("3rd", super::ordinal(3));
+        assert_eq!("4th", super::ordinal(4));
+        assert_eq!("11th", super::ordinal(11));
+        assert_eq!("12th", super::ordinal(12));
+        assert_eq!("13th", super::ordinal(13));
+        assert_eq!("21st", super::ordinal(21));
+        assert_eq!("112th", super::ordinal(112));
+    }
+
+    #[test]
+    fn display() {
+        let results = rankings().results(3).with_labels(["Ann", "Bo"]);
+
+        assert_eq!(
+            "Ann 1.67 avg [1st 66.67%, 2nd 0.00%, 3rd 33.33%]\n\
+             Bo 1.33 avg [1st 66.67%, 2nd 33.33%, 3rd 0.00%]\n\
+             Player #3 2.67 avg [1st 0.00%, 2nd 33.33%, 3rd 66.67%]\n",
+            results.to_string()
+        );
+    }
+}
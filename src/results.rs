@@ -1,17 +1,39 @@
+use crate::confidence::{ConfidenceInterval, IntervalMethod};
+use crate::flag::Flag;
+use crate::heads_up::HeadsUp;
 use crate::util::Util;
-use crate::win::Win;
 use crate::wins::Wins;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
 /// # PHASE 2.2/Step 4: Results
 ///
 /// `WinResults` is a utility state class designed to make it as easy as possible to get and display
 /// winning and tie percentages for any game.
+///
+/// `split_ways` holds, for each player, how many ways the pot was split when they tied, mapped
+/// to the number of times that happened. A two-way chop is `2`, a three-way chop is `3`, etc.
+/// It's what lets us work out a player's [equity](WinResults::equity), where a three-way chop
+/// isn't worth the same as a two-way one.
+///
+/// `co_winners` goes one step further, and holds for each player the exact set of other players
+/// (as zero based indexes) they tied with, mapped to the number of times that happened.
+///
+/// `labels` are optional names for the players, by index, like `"Negreanu"` or `"A♠ K♠"`. Every
+/// report uses them in place of `Player #1`, `Player #2`, etc. A player without one, either
+/// because the `Vec` is too short or their label is blank, keeps their number.
+///
+/// `no_winner` is the number of cases nobody won. They're part of `case_count`, so they pull
+/// everybody's percentages down, and get a line of their own in the report.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct WinResults {
     pub case_count: usize,
     pub player_count: usize,
+    pub no_winner: usize,
     pub v: Vec<(usize, usize)>,
+    pub split_ways: Vec<BTreeMap<usize, usize>>,
+    pub co_winners: Vec<BTreeMap<Vec<usize>, usize>>,
+    pub labels: Vec<String>,
 }
 
 impl WinResults {
@@ -92,20 +114,371 @@ impl WinResults {
     ///     results
     /// }
     /// ```
+    ///
+    /// ## Single pass
+    ///
+    /// This used to call `Wins::wins_for()` once for every player, which meant walking the entire
+    /// history of outcomes `player_count` times. Now it makes one sweep over the distinct
+    /// `PlayerFlag` patterns, crediting each player whose bit is set, and counting the cases
+    /// along the way. Bits at or above `player_count` are ignored, same as before. Use
+    /// [`WinResults::try_from_wins()`] if you'd rather hear about them.
+    ///
+    /// Works with any [`Flag`] width, so `player_count` can go as high as the width of the flag.
     #[must_use]
-    pub fn from_wins(wins: &Wins, player_count: usize) -> WinResults {
-        let mut results = Self {
-            case_count: wins.len(),
+    pub fn from_wins<F: Flag>(wins: &Wins<F>, player_count: usize) -> WinResults {
+        let mut results = WinResults::empty(player_count);
+        for (count, x) in wins.iter() {
+            results.record(count, x);
+        }
+        results.record_no_winner(wins.no_winner());
+        results
+    }
+
+    /// ```
+    /// use wincounter::heads_up::HeadsUp;
+    /// use wincounter::results::WinResults;
+    ///
+    /// let the_hand = WinResults::from(&HeadsUp::new(1_365_284, 314_904, 32_116))
+    ///     .with_labels(["Negreanu", "Hansen"]);
+    ///
+    /// assert_eq!(Some(1), the_hand.index_of("Hansen"));
+    /// assert_eq!(
+    ///     "Negreanu 81.6% (79.73%/1.88%) [1365284/32116]\nHansen 20.3% (18.39%/1.88%) [314904/32116]\n",
+    ///     the_hand.to_string()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The player's label, or `Player #1` etc. if they don't have one.
+    #[must_use]
+    pub fn label(&self, player_index: usize) -> String {
+        label_or(&self.labels, player_index, "Player #")
+    }
+
+    /// The player's label, or `#1` etc. if they don't have one, for when there's less room.
+    #[must_use]
+    pub fn short_label(&self, player_index: usize) -> String {
+        label_or(&self.labels, player_index, "#")
+    }
+
+    /// Looks a player up by their label, so that everything that takes a `player_index` can be
+    /// used by name.
+    #[must_use]
+    pub fn index_of(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|l| !l.is_empty() && l == label)
+    }
+
+    /// Results for `player_count` players with nothing recorded yet.
+    pub(crate) fn empty(player_count: usize) -> WinResults {
+        Self {
             player_count,
+            v: vec![(0, 0); player_count],
+            split_ways: vec![BTreeMap::new(); player_count],
+            co_winners: vec![BTreeMap::new(); player_count],
             ..Default::default()
-        };
+        }
+    }
 
-        for i in 0..player_count {
-            let (total_wins, ties) = wins.wins_for(Win::from_index(i));
-            results.v.push((total_wins - ties, ties));
+    /// Credits `x` outcomes of the `count` pattern to every player in it.
+    pub(crate) fn record<F: Flag>(&mut self, count: F, x: usize) {
+        self.case_count += x;
+        let ways = count.players();
+        for i in count.indexes() {
+            if let Some((wins, ties)) = self.v.get_mut(i) {
+                if ways > 1 {
+                    *ties += x;
+                    *self.split_ways[i].entry(ways).or_default() += x;
+                    let others = count.indexes().filter(|j| *j != i).collect();
+                    *self.co_winners[i].entry(others).or_default() += x;
+                } else {
+                    *wins += x;
+                }
+            }
         }
+    }
 
-        results
+    pub(crate) fn record_no_winner(&mut self, x: usize) {
+        self.case_count += x;
+        self.no_winner += x;
+    }
+
+    /// Adds another set of results for the same players into this one.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn merge(&mut self, other: WinResults) {
+        self.case_count += other.case_count;
+        self.no_winner += other.no_winner;
+        for (mine, (wins, ties)) in self.v.iter_mut().zip(other.v) {
+            mine.0 += wins;
+            mine.1 += ties;
+        }
+        for (mine, theirs) in self.split_ways.iter_mut().zip(other.split_ways) {
+            for (ways, x) in theirs {
+                *mine.entry(ways).or_default() += x;
+            }
+        }
+        for (mine, theirs) in self.co_winners.iter_mut().zip(other.co_winners) {
+            for (others, x) in theirs {
+                *mine.entry(others).or_default() += x;
+            }
+        }
+    }
+
+    /// Returns the player's share of the pots, where each outcome is worth one pot. Winning alone
+    /// takes the whole pot, and tying with `k - 1` other players takes `1/k` of it. This is the
+    /// number that poker tools report as equity.
+    ///
+    /// ```
+    /// use wincounter::results::WinResults;
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 4);
+    /// wins.add_x(Win::FIRST | Win::SECOND, 2);
+    /// wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 3);
+    ///
+    /// let results = WinResults::from_wins(&wins, 3);
+    ///
+    /// assert_eq!(6.0, results.equity(0));
+    /// assert_eq!(2.0, results.equity(1));
+    /// assert_eq!(1.0, results.equity(2));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn equity(&self, player_index: usize) -> f64 {
+        let (wins, _) = self.wins_and_ties(player_index);
+        let splits: f64 = self.split_ways.get(player_index).map_or(0.0, |ways| {
+            ways.iter().map(|(k, x)| *x as f64 / *k as f64).sum()
+        });
+        wins as f64 + splits
+    }
+
+    /// The player's [equity](WinResults::equity) as a percentage of all cases. Across every
+    /// player, these only add up to 100% when every case has a winner who was counted: cases
+    /// with [no winner](WinResults::no_winner), and wins for players at or past `player_count`,
+    /// leave the total short.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn equity_percentage(&self, player_index: usize) -> f32 {
+        match self.case_count {
+            0 => 0_f32,
+            total => (self.equity(player_index) * 100.0 / total as f64) as f32,
+        }
+    }
+
+    /// Returns the sets of other players that the player tied with, along with the number of
+    /// times, most frequent first. Ties in frequency are ordered by the player indexes.
+    ///
+    /// ```
+    /// use wincounter::results::WinResults;
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST | Win::SECOND, 2);
+    /// wins.add_x(Win::FIRST | Win::THIRD, 5);
+    /// wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 1);
+    ///
+    /// let results = WinResults::from_wins(&wins, 3);
+    ///
+    /// assert_eq!(
+    ///     vec![(vec![2], 5), (vec![1], 2), (vec![1, 2], 1)],
+    ///     results.ties_by_co_winners(0)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn ties_by_co_winners(&self, player_index: usize) -> Vec<(Vec<usize>, usize)> {
+        let mut v: Vec<(Vec<usize>, usize)> = self
+            .co_winners
+            .get(player_index)
+            .map(|m| m.iter().map(|(k, x)| (k.clone(), *x)).collect())
+            .unwrap_or_default();
+        v.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        v
+    }
+
+    /// The player's `co_winners`, but only if they account for every one of their ties. Results
+    /// read back from CSV, which doesn't keep them, have none.
+    pub(crate) fn complete_co_winners(
+        &self,
+        player_index: usize,
+    ) -> Option<&BTreeMap<Vec<usize>, usize>> {
+        let (_, ties) = self.wins_and_ties(player_index);
+        self.co_winners
+            .get(player_index)
+            .filter(|co_winners| co_winners.values().sum::<usize>() == ties)
+    }
+
+    /// Returns the number of times the player tied, grouped by the number of ways the pot was
+    /// split, smallest split first.
+    #[must_use]
+    pub fn ties_by_ways(&self, player_index: usize) -> Vec<(usize, usize)> {
+        self.split_ways
+            .get(player_index)
+            .map(|m| m.iter().map(|(k, x)| (*k, *x)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Text version of the player's tie breakdown, first by the number of ways the pot was split,
+    /// then by who they split it with. Players are numbered from one, same as in the `Display`
+    /// implementation.
+    ///
+    /// ```
+    /// use wincounter::results::WinResults;
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 2);
+    /// wins.add_x(Win::FIRST | Win::SECOND, 1);
+    /// wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 1);
+    ///
+    /// let results = WinResults::from_wins(&wins, 3);
+    ///
+    /// assert_eq!(
+    ///     "2-way 1 (25.00%), 3-way 1 (25.00%); with #2 1 (25.00%), with #2 #3 1 (25.00%)",
+    ///     results.ties_to_string(0)
+    /// );
+    /// assert_eq!("no ties", results.ties_to_string(3));
+    /// ```
+    #[must_use]
+    pub fn ties_to_string(&self, player_index: usize) -> String {
+        let ways = self.ties_by_ways(player_index);
+        if ways.is_empty() {
+            return "no ties".to_string();
+        }
+        let ways: Vec<String> = ways
+            .iter()
+            .map(|(k, x)| format!("{k}-way {x} ({:.2}%)", self.percentage_of_cases(*x)))
+            .collect();
+        let with: Vec<String> = self
+            .ties_by_co_winners(player_index)
+            .iter()
+            .map(|(others, x)| {
+                let others: Vec<String> = others.iter().map(|j| self.short_label(*j)).collect();
+                format!(
+                    "with {} {x} ({:.2}%)",
+                    others.join(" "),
+                    self.percentage_of_cases(*x)
+                )
+            })
+            .collect();
+        format!("{}; {}", ways.join(", "), with.join(", "))
+    }
+
+    /// A line for each player with their [tie breakdown](WinResults::ties_to_string).
+    #[must_use]
+    pub fn ties_report(&self) -> String {
+        let lines: Vec<String> = (0..self.v.len())
+            .map(|i| format!("{} {}\n", self.label(i), self.ties_to_string(i)))
+            .collect();
+        lines.concat()
+    }
+
+    fn percentage_of_cases(&self, number: usize) -> f32 {
+        Util::calculate_percentage(number, self.case_count)
+    }
+
+    /// Confidence interval around the player's win alone percentage, for when the `Wins` came
+    /// from random sampling instead of a full enumeration. `confidence` is between 0 and 1, so
+    /// `0.95` for 95%. Returns `None` if it isn't.
+    ///
+    /// ```
+    /// use wincounter::confidence::IntervalMethod;
+    /// use wincounter::results::WinResults;
+    /// use wincounter::win::Win;
+    /// use wincounter::wins::Wins;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 7_970);
+    /// wins.add_x(Win::SECOND, 1_842);
+    /// wins.add_x(Win::FIRST | Win::SECOND, 188);
+    ///
+    /// let results = WinResults::from_wins(&wins, 2);
+    /// let ci = results.win_interval(0, 0.95, IntervalMethod::Wilson).unwrap();
+    ///
+    /// assert_eq!("79.70% ± 0.80% [78.90%, 80.48%]", ci.to_string());
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn win_interval(
+        &self,
+        player_index: usize,
+        confidence: f64,
+        method: IntervalMethod,
+    ) -> Option<ConfidenceInterval> {
+        let (wins, _) = self.wins_and_ties(player_index);
+        ConfidenceInterval::binomial(wins as f64, self.case_count, confidence, method)
+    }
+
+    /// Confidence interval around the player's tie percentage. See
+    /// [`win_interval()`](WinResults::win_interval).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn tie_interval(
+        &self,
+        player_index: usize,
+        confidence: f64,
+        method: IntervalMethod,
+    ) -> Option<ConfidenceInterval> {
+        let (_, ties) = self.wins_and_ties(player_index);
+        ConfidenceInterval::binomial(ties as f64, self.case_count, confidence, method)
+    }
+
+    /// Confidence interval around the player's [equity percentage](WinResults::equity_percentage).
+    ///
+    /// Equity isn't a simple yes or no for each outcome, since a chop is worth a fraction of a
+    /// pot. `IntervalMethod::Normal` uses the actual spread of those shares. `Wilson` and
+    /// `ClopperPearson` treat the equity as if it were a count of whole wins, which can only
+    /// make the interval wider, never narrower.
+    #[must_use]
+    pub fn equity_interval(
+        &self,
+        player_index: usize,
+        confidence: f64,
+        method: IntervalMethod,
+    ) -> Option<ConfidenceInterval> {
+        match method {
+            IntervalMethod::Normal => ConfidenceInterval::normal(
+                f64::from(self.equity_percentage(player_index)),
+                self.equity_standard_error(player_index),
+                confidence,
+            ),
+            _ => ConfidenceInterval::binomial(
+                self.equity(player_index),
+                self.case_count,
+                confidence,
+                method,
+            ),
+        }
+    }
+
+    /// Standard error of the player's [equity percentage](WinResults::equity_percentage), based
+    /// on the spread of the share of the pot they got in each outcome.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn equity_standard_error(&self, player_index: usize) -> f64 {
+        if self.case_count == 0 {
+            return 0.0;
+        }
+        let n = self.case_count as f64;
+        let (wins, _) = self.wins_and_ties(player_index);
+        let squares: f64 = self.split_ways.get(player_index).map_or(0.0, |ways| {
+            ways.iter()
+                .map(|(k, x)| *x as f64 / (*k as f64 * *k as f64))
+                .sum()
+        });
+        let mean = self.equity(player_index) / n;
+        let variance = ((wins as f64 + squares) / n - mean * mean).max(0.0);
+        (variance / n).sqrt() * 100.0
     }
 
     /// This function is there to make it easy to create text based displays of a player's chances
@@ -155,6 +528,49 @@ impl WinResults {
         let (wins, ties) = self.wins_and_ties(player_index);
         Util::calculate_percentage(wins + ties, self.case_count)
     }
+
+    /// The percentage of cases that nobody won.
+    #[must_use]
+    pub fn no_winner_percentage(&self) -> f32 {
+        Util::calculate_percentage(self.no_winner, self.case_count)
+    }
+}
+
+/// The first player wins `first_wins` times, the second `second_wins` times, and every tie is a
+/// two-way chop between them.
+impl From<&HeadsUp> for WinResults {
+    fn from(hup: &HeadsUp) -> Self {
+        let split = if hup.ties > 0 {
+            BTreeMap::from([(2, hup.ties)])
+        } else {
+            BTreeMap::new()
+        };
+        let co_winners = |other: usize| {
+            if hup.ties > 0 {
+                BTreeMap::from([(vec![other], hup.ties)])
+            } else {
+                BTreeMap::new()
+            }
+        };
+        WinResults {
+            case_count: hup.total(),
+            player_count: 2,
+            no_winner: hup.no_winner,
+            v: vec![(hup.first_wins, hup.ties), (hup.second_wins, hup.ties)],
+            split_ways: vec![split.clone(), split],
+            co_winners: vec![co_winners(1), co_winners(0)],
+            labels: Vec::new(),
+        }
+    }
+}
+
+/// The label at `i`, or the player's number after `prefix` if it's missing or empty. Everything
+/// else that carries its own labels goes through here too, so they all fall back the same way.
+pub(crate) fn label_or(labels: &[String], i: usize, prefix: &str) -> String {
+    match labels.get(i) {
+        Some(label) if !label.is_empty() => label.clone(),
+        _ => format!("{prefix}{}", i + 1),
+    }
 }
 
 /// Right now I am irritated that it ends with a new line, but I don't really want to deal with it
@@ -162,19 +578,36 @@ impl WinResults {
 /// 56 years old. The brain cells are dying fast. R.I.P. 🪦
 ///
 /// TODO TD: Trim final new line.
+///
+/// Cases nobody won get a `No winner` line at the end, but only if there were any.
 impl Display for WinResults {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for i in 0..self.v.len() {
-            writeln!(f, "Player #{} {}", i + 1, self.player_to_string(i))?;
+            writeln!(f, "{} {}", self.label(i), self.player_to_string(i))?;
+        }
+        if self.no_winner > 0 {
+            writeln!(
+                f,
+                "No winner {:.2}% [{}]",
+                self.no_winner_percentage(),
+                self.no_winner
+            )?;
         }
         Ok(())
     }
 }
 
 #[cfg(test)]
-#[allow(non_snake_case)]
+#[allow(
+    non_snake_case,
+    clippy::float_cmp,
+    clippy::get_first,
+    clippy::unreadable_literal,
+    clippy::unwrap_used
+)]
 mod util__wincounter__results__tests {
     use super::*;
+    use crate::win::Win;
 
     fn the_hand_as_wins() -> Wins {
         let mut wins = Wins::default();
@@ -196,6 +629,44 @@ mod util__wincounter__results__tests {
         assert_eq!(&(314_904, 32_116), results.v.get(1).unwrap());
     }
 
+    #[test]
+    fn from_wins__no_winner() {
+        let mut wins = Wins::from(vec![Win::FIRST, Win::FIRST | Win::SECOND]);
+        wins.add_no_winner_x(2);
+
+        let results = WinResults::from_wins(&wins, 2);
+
+        assert_eq!(4, results.case_count);
+        assert_eq!(2, results.no_winner);
+        assert_eq!(50.0, results.no_winner_percentage());
+        assert_eq!(37.5, results.equity_percentage(0));
+        assert_eq!(
+            "Player #1 50.0% (25.00%/25.00%) [1/1]\n\
+             Player #2 25.0% (0.00%/25.00%) [0/1]\n\
+             No winner 50.00% [2]\n",
+            results.to_string()
+        );
+        assert_eq!(results, WinResults::from(&HeadsUp::from(&results)));
+    }
+
+    #[test]
+    fn from_wins__multiway() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 10);
+        wins.add_x(Win::SECOND | Win::THIRD, 4);
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 2);
+        wins.add_x(Win::FORTH, 3);
+
+        let results = WinResults::from_wins(&wins, 3);
+
+        assert_eq!(19, results.case_count);
+        assert_eq!(vec![(10, 2), (0, 6), (0, 6)], results.v);
+        for i in 0..3 {
+            let (total, ties) = wins.wins_for(Win::from_index(i));
+            assert_eq!((total - ties, ties), results.wins_and_ties(i));
+        }
+    }
+
     #[test]
     fn player_to_string() {
         let results = WinResults::from_wins(&the_hand_as_wins(), 2);
@@ -251,6 +722,167 @@ mod util__wincounter__results__tests {
         assert_eq!(0.0, results.wins_total_percentage(3));
     }
 
+    #[test]
+    fn equity() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        assert_eq!(1_381_342.0, results.equity(0));
+        assert_eq!(330_962.0, results.equity(1));
+        assert_eq!(0.0, results.equity(2));
+    }
+
+    #[test]
+    fn equity__multiway() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST | Win::SECOND, 6);
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 6);
+
+        let results = WinResults::from_wins(&wins, 3);
+
+        assert_eq!((0, 12), results.wins_and_ties(0));
+        assert_eq!((0, 12), results.wins_and_ties(1));
+        assert_eq!(5.0, results.equity(0));
+        assert_eq!(5.0, results.equity(1));
+        assert_eq!(2.0, results.equity(2));
+        assert_eq!(BTreeMap::from([(2, 6), (3, 6)]), results.split_ways[0]);
+    }
+
+    #[test]
+    fn equity_percentage() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        assert_eq!(80.67154, results.equity_percentage(0));
+        assert_eq!(19.32846, results.equity_percentage(1));
+        assert_eq!(0.0, results.equity_percentage(2));
+        assert_eq!(0.0, WinResults::default().equity_percentage(0));
+    }
+
+    #[test]
+    fn co_winners() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST | Win::SECOND, 3);
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 2);
+        wins.add_x(Win::SECOND | Win::FIFTH, 1);
+
+        let results = WinResults::from_wins(&wins, 3);
+
+        assert_eq!(
+            BTreeMap::from([(vec![1], 3), (vec![1, 2], 2)]),
+            results.co_winners[0]
+        );
+        assert_eq!(
+            BTreeMap::from([(vec![0], 3), (vec![0, 2], 2), (vec![4], 1)]),
+            results.co_winners[1]
+        );
+        assert_eq!(BTreeMap::from([(vec![0, 1], 2)]), results.co_winners[2]);
+    }
+
+    #[test]
+    fn ties_by_co_winners() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        assert_eq!(vec![(vec![1], 32_116)], results.ties_by_co_winners(0));
+        assert_eq!(vec![(vec![0], 32_116)], results.ties_by_co_winners(1));
+        assert!(results.ties_by_co_winners(2).is_empty());
+    }
+
+    #[test]
+    fn ties_by_ways() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 4);
+        wins.add_x(Win::FIRST | Win::THIRD, 1);
+
+        let results = WinResults::from_wins(&wins, 3);
+
+        assert_eq!(vec![(2, 1), (3, 4)], results.ties_by_ways(0));
+        assert_eq!(vec![(3, 4)], results.ties_by_ways(1));
+        assert!(results.ties_by_ways(3).is_empty());
+    }
+
+    #[test]
+    fn ties_report() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        assert_eq!(
+            "Player #1 2-way 32116 (1.88%); with #2 32116 (1.88%)\nPlayer #2 2-way 32116 (1.88%); with #1 32116 (1.88%)\n",
+            results.ties_report()
+        );
+    }
+
+    #[test]
+    fn win_interval() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+        let ci = results
+            .win_interval(0, 0.95, IntervalMethod::Normal)
+            .unwrap();
+
+        assert_eq!(
+            f64::from(results.wins_and_ties_percentages(0).0).round(),
+            ci.estimate.round()
+        );
+        assert!(ci.lower < ci.estimate && ci.estimate < ci.upper);
+        assert!(results
+            .win_interval(0, 1.5, IntervalMethod::Wilson)
+            .is_none());
+    }
+
+    #[test]
+    fn tie_interval() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+        let ci = results
+            .tie_interval(1, 0.99, IntervalMethod::ClopperPearson)
+            .unwrap();
+
+        assert!(ci.lower < 1.8756 && 1.8756 < ci.upper);
+        assert!(ci.margin() < 0.1);
+    }
+
+    #[test]
+    fn equity_interval() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 50);
+        wins.add_x(Win::SECOND, 30);
+        wins.add_x(Win::FIRST | Win::SECOND, 20);
+        let results = WinResults::from_wins(&wins, 2);
+
+        let normal = results
+            .equity_interval(0, 0.95, IntervalMethod::Normal)
+            .unwrap();
+        let wilson = results
+            .equity_interval(0, 0.95, IntervalMethod::Wilson)
+            .unwrap();
+
+        assert_eq!(60.0, normal.estimate);
+        assert!(normal.margin() < wilson.margin());
+    }
+
+    #[test]
+    fn equity_standard_error() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 50);
+        wins.add_x(Win::SECOND, 30);
+        wins.add_x(Win::FIRST | Win::SECOND, 20);
+        let results = WinResults::from_wins(&wins, 2);
+
+        // Shares are 1 (x50), 0 (x30) and 0.5 (x20): mean 0.6, variance 0.55 - 0.36 = 0.19.
+        assert_eq!(
+            4.3589,
+            (results.equity_standard_error(0) * 10_000.0).round() / 10_000.0
+        );
+        assert_eq!(0.0, WinResults::default().equity_standard_error(0));
+    }
+
+    #[test]
+    fn from__heads_up() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        assert_eq!(results, WinResults::from(&HeadsUp::from(&results)));
+        assert_eq!(
+            WinResults::from_wins(&Wins::from(vec![Win::FIRST]), 2),
+            WinResults::from(&HeadsUp::new(1, 0, 0))
+        );
+    }
+
     /// I like to organize my tests to match the order they fall in the source. I generally
     /// structure them as:
     ///
@@ -258,6 +890,29 @@ mod util__wincounter__results__tests {
     /// * functions on self
     /// * static functions
     /// * trait implementations
+    #[test]
+    fn labels() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 3).with_labels(["Negreanu", ""]);
+
+        assert_eq!("Negreanu", results.label(0));
+        assert_eq!("Player #2", results.label(1));
+        assert_eq!("#3", results.short_label(2));
+        assert_eq!(Some(0), results.index_of("Negreanu"));
+        assert_eq!(None, results.index_of(""));
+        assert_eq!(None, results.index_of("Hansen"));
+    }
+
+    #[test]
+    fn ties_report__labels() {
+        let results =
+            WinResults::from_wins(&the_hand_as_wins(), 2).with_labels(["Negreanu", "Hansen"]);
+
+        assert_eq!(
+            "Negreanu 2-way 32116 (1.88%); with Hansen 32116 (1.88%)\nHansen 2-way 32116 (1.88%); with Negreanu 32116 (1.88%)\n",
+            results.ties_report()
+        );
+    }
+
     #[test]
     fn display() {
         let results = WinResults::from_wins(&the_hand_as_wins(), 2);
@@ -0,0 +1,511 @@
+//! The serialized form of [`Wins`] and [`WinResults`].
+//!
+//! Both types are serialized through the record structs in this module rather than their
+//! internals, so that the format stays put while the code underneath it changes. Every record
+//! carries a `version`, which is currently [`SCHEMA_VERSION`]. Deserializing a record from a
+//! newer version of the schema than this crate knows about is an error.
+//!
+//! Version 2 added the `no_winner` counts and the labels. Version 1 records don't have either,
+//! and still load.
+//!
+//! ## `Wins`
+//!
+//! A `Wins` is stored as its histogram of flag patterns, not as one entry per outcome. `width`
+//! is the number of bits in the flag type it was saved from. Patterns are plain integers, so
+//! a `Wins<u16>` can be loaded as a `Wins<u64>`. Going the other way is an error if any of the
+//! patterns don't fit. Outcomes that nobody won are a separate `no_winner` count, which is left
+//! out when there aren't any.
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "width": 16,
+//!   "patterns": [[1, 1365284], [2, 314904], [3, 32116]]
+//! }
+//! ```
+//!
+//! ## `Tally`
+//!
+//! A [`Tally`] is stored the same way as a `Wins`, with its `player_count` and `labels` added
+//! when it has them. Loading a `Tally` record as a plain `Wins` just skips them.
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "width": 16,
+//!   "patterns": [[1, 1365284], [2, 314904], [3, 32116]],
+//!   "player_count": 2,
+//!   "labels": ["Negreanu", "Hansen"]
+//! }
+//! ```
+//!
+//! ## `WinResults`
+//!
+//! A `WinResults` is stored as one record per player. The counts are what matter when it's
+//! loaded back in. The percentages are there for whoever is reading the file, and are worked
+//! out again from the counts on the way in. Players with a label have a `label` as well, and
+//! there's a top level `no_winner` count if any cases were won by nobody.
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "case_count": 1712304,
+//!   "player_count": 2,
+//!   "players": [
+//!     {
+//!       "index": 0,
+//!       "label": "Negreanu",
+//!       "wins": 1365284,
+//!       "ties": 32116,
+//!       "win_percentage": 79.73374,
+//!       "tie_percentage": 1.8756015,
+//!       "equity_percentage": 80.67154,
+//!       "split_ways": { "2": 32116 },
+//!       "co_winners": [{ "players": [1], "count": 32116 }]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! ```
+//! use wincounter::results::WinResults;
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let mut wins = Wins::default();
+//! wins.add_x(Win::FIRST, 80);
+//! wins.add_x(Win::FIRST | Win::SECOND, 5);
+//!
+//! let json = serde_json::to_string(&wins).unwrap();
+//! assert_eq!(r#"{"version":2,"width":16,"patterns":[[1,80],[3,5]]}"#, json);
+//!
+//! let wider: Wins<u64> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(80, wider.count(1));
+//! ```
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::tally::Tally;
+use crate::util::Util;
+use crate::wins::Wins;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// The current version of the serialized format.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WinsRecord {
+    pub version: u32,
+    pub width: usize,
+    pub patterns: Vec<(u128, usize)>,
+    #[serde(default, skip_serializing_if = "Util::is_zero")]
+    pub no_winner: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl<F: Flag> From<&Wins<F>> for WinsRecord {
+    fn from(wins: &Wins<F>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            width: F::CAPACITY,
+            patterns: wins.iter().map(|(count, x)| (count.to_u128(), x)).collect(),
+            no_winner: wins.no_winner(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<F: Flag> TryFrom<WinsRecord> for Wins<F> {
+    type Error = String;
+
+    fn try_from(record: WinsRecord) -> Result<Self, Self::Error> {
+        check_version(record.version)?;
+        let mut wins = Wins::new();
+        for (pattern, x) in record.patterns {
+            let count = F::from_u128(pattern).ok_or_else(|| {
+                format!(
+                    "pattern {pattern:#b} doesn't fit in {} bits (saved as {} bits)",
+                    F::CAPACITY,
+                    record.width
+                )
+            })?;
+            wins.add_x(count, x);
+        }
+        wins.add_no_winner_x(record.no_winner);
+        Ok(wins)
+    }
+}
+
+impl<F: Flag> Serialize for Wins<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WinsRecord::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, F: Flag> Deserialize<'de> for Wins<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Wins::try_from(WinsRecord::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<F: Flag> From<&Tally<F>> for WinsRecord {
+    fn from(tally: &Tally<F>) -> Self {
+        Self {
+            player_count: tally.player_count,
+            labels: tally.labels.clone(),
+            ..WinsRecord::from(&tally.wins)
+        }
+    }
+}
+
+impl<F: Flag> TryFrom<WinsRecord> for Tally<F> {
+    type Error = String;
+
+    fn try_from(mut record: WinsRecord) -> Result<Self, Self::Error> {
+        let player_count = record.player_count;
+        let labels = std::mem::take(&mut record.labels);
+        Ok(Tally {
+            wins: Wins::try_from(record)?,
+            player_count,
+            labels,
+        })
+    }
+}
+
+impl<F: Flag> Serialize for Tally<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WinsRecord::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, F: Flag> Deserialize<'de> for Tally<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Tally::try_from(WinsRecord::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoWinnersRecord {
+    pub players: Vec<usize>,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerRecord {
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub wins: usize,
+    pub ties: usize,
+    pub win_percentage: f32,
+    pub tie_percentage: f32,
+    pub equity_percentage: f32,
+    #[serde(default)]
+    pub split_ways: BTreeMap<usize, usize>,
+    #[serde(default)]
+    pub co_winners: Vec<CoWinnersRecord>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WinResultsRecord {
+    pub version: u32,
+    pub case_count: usize,
+    pub player_count: usize,
+    #[serde(default, skip_serializing_if = "Util::is_zero")]
+    pub no_winner: usize,
+    pub players: Vec<PlayerRecord>,
+}
+
+impl From<&WinResults> for WinResultsRecord {
+    fn from(results: &WinResults) -> Self {
+        let players = (0..results.v.len())
+            .map(|i| {
+                let (wins, ties) = results.wins_and_ties(i);
+                let (win_percentage, tie_percentage) = results.wins_and_ties_percentages(i);
+                PlayerRecord {
+                    index: i,
+                    label: results.labels.get(i).filter(|l| !l.is_empty()).cloned(),
+                    wins,
+                    ties,
+                    win_percentage,
+                    tie_percentage,
+                    equity_percentage: results.equity_percentage(i),
+                    split_ways: results.split_ways.get(i).cloned().unwrap_or_default(),
+                    co_winners: results
+                        .co_winners
+                        .get(i)
+                        .map(|m| {
+                            m.iter()
+                                .map(|(players, count)| CoWinnersRecord {
+                                    players: players.clone(),
+                                    count: *count,
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        Self {
+            version: SCHEMA_VERSION,
+            case_count: results.case_count,
+            player_count: results.player_count,
+            no_winner: results.no_winner,
+            players,
+        }
+    }
+}
+
+impl TryFrom<WinResultsRecord> for WinResults {
+    type Error = String;
+
+    fn try_from(record: WinResultsRecord) -> Result<Self, Self::Error> {
+        check_version(record.version)?;
+        if record.players.len() != record.player_count {
+            return Err(format!(
+                "{} player records for a player count of {}",
+                record.players.len(),
+                record.player_count
+            ));
+        }
+        let mut results = WinResults {
+            case_count: record.case_count,
+            player_count: record.player_count,
+            no_winner: record.no_winner,
+            ..Default::default()
+        };
+        for (i, player) in record.players.into_iter().enumerate() {
+            if player.index != i {
+                return Err(format!(
+                    "player records out of order: expected index {i}, found {}",
+                    player.index
+                ));
+            }
+            results.labels.push(player.label.unwrap_or_default());
+            results.v.push((player.wins, player.ties));
+            results.split_ways.push(player.split_ways);
+            results.co_winners.push(
+                player
+                    .co_winners
+                    .into_iter()
+                    .map(|r| (r.players, r.count))
+                    .collect(),
+            );
+        }
+        if results.labels.iter().all(String::is_empty) {
+            results.labels.clear();
+        }
+        Ok(results)
+    }
+}
+
+impl Serialize for WinResults {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WinResultsRecord::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WinResults {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        WinResults::try_from(WinResultsRecord::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version == 0 || version > SCHEMA_VERSION {
+        Err(format!(
+            "unsupported schema version {version} (this crate reads up to {SCHEMA_VERSION})"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__schema__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn the_hand_as_wins() -> Wins {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST, 1_365_284);
+        wins.add_x(Win::SECOND, 314_904);
+        wins.add_x(Win::FIRST | Win::SECOND, 32_116);
+        wins
+    }
+
+    #[test]
+    fn wins__round_trip() {
+        let wins = the_hand_as_wins();
+
+        let json = serde_json::to_string(&wins).unwrap();
+        let back: Wins = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            r#"{"version":2,"width":16,"patterns":[[1,1365284],[2,314904],[3,32116]]}"#,
+            json
+        );
+        assert_eq!(wins, back);
+    }
+
+    #[test]
+    fn wins__round_trip__u128() {
+        let mut wins: Wins<u128> = Wins::new();
+        wins.add_x(1 << 127 | 1, 3);
+        wins.add(0);
+
+        let json = serde_json::to_string(&wins).unwrap();
+        let back: Wins<u128> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(wins, back);
+    }
+
+    #[test]
+    fn no_winner__round_trip() {
+        let mut wins = the_hand_as_wins();
+        wins.add_no_winner_x(9);
+        let results = WinResults::from_wins(&wins, 2);
+
+        let json = serde_json::to_string(&wins).unwrap();
+        let value = serde_json::to_value(&results).unwrap();
+
+        assert!(json.contains(r#""no_winner":9"#));
+        assert_eq!(wins, serde_json::from_str::<Wins>(&json).unwrap());
+        assert_eq!(9, value["no_winner"]);
+        assert_eq!(results, serde_json::from_value(value).unwrap());
+        assert!(
+            serde_json::to_value(WinResults::from_wins(&the_hand_as_wins(), 2))
+                .unwrap()
+                .get("no_winner")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn wins__too_narrow() {
+        let mut wins: Wins<u32> = Wins::new();
+        wins.add(1 << 20);
+
+        let json = serde_json::to_string(&wins).unwrap();
+        let error = serde_json::from_str::<Wins<u16>>(&json).unwrap_err();
+
+        assert!(error.to_string().contains("doesn't fit in 16 bits"));
+    }
+
+    #[test]
+    fn wins__unsupported_version() {
+        let json = r#"{"version":3,"width":16,"patterns":[]}"#;
+
+        let error = serde_json::from_str::<Wins>(json).unwrap_err();
+
+        assert!(error.to_string().contains("unsupported schema version 3"));
+    }
+
+    #[test]
+    fn wins__version_1() {
+        let json = r#"{"version":1,"width":16,"patterns":[[1,1365284],[2,314904],[3,32116]]}"#;
+
+        assert_eq!(the_hand_as_wins(), serde_json::from_str(json).unwrap());
+    }
+
+    #[test]
+    fn win_results__round_trip() {
+        let mut wins = the_hand_as_wins();
+        wins.add_x(Win::FIRST | Win::SECOND | Win::THIRD, 7);
+        let results = WinResults::from_wins(&wins, 3);
+
+        let json = serde_json::to_string(&results).unwrap();
+        let back: WinResults = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(results, back);
+    }
+
+    #[test]
+    fn win_results__record() {
+        let results = WinResults::from_wins(&the_hand_as_wins(), 2);
+
+        let value = serde_json::to_value(&results).unwrap();
+
+        assert_eq!(2, value["version"]);
+        assert_eq!(1_712_304, value["case_count"]);
+        assert_eq!(2, value["player_count"]);
+        assert_eq!(1, value["players"][1]["index"]);
+        assert_eq!(314_904, value["players"][1]["wins"]);
+        assert_eq!(32_116, value["players"][1]["ties"]);
+        assert_eq!(32_116, value["players"][1]["split_ways"]["2"]);
+        assert_eq!(
+            serde_json::json!([{ "players": [0], "count": 32116 }]),
+            value["players"][1]["co_winners"]
+        );
+    }
+
+    #[test]
+    fn win_results__labels() {
+        let results =
+            WinResults::from_wins(&the_hand_as_wins(), 3).with_labels(["Negreanu", "", "Ivey"]);
+
+        let value = serde_json::to_value(&results).unwrap();
+        let back: WinResults = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!("Negreanu", value["players"][0]["label"]);
+        assert!(value["players"][1].get("label").is_none());
+        assert_eq!(results, back);
+        assert!(
+            serde_json::to_value(WinResults::from_wins(&the_hand_as_wins(), 2)).unwrap()["players"]
+                [0]
+            .get("label")
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn tally__round_trip() {
+        let tally = Tally::new(the_hand_as_wins(), 2).with_labels(["Negreanu", "Hansen"]);
+
+        let json = serde_json::to_string(&tally).unwrap();
+        let back: Tally = serde_json::from_str(&json).unwrap();
+        let wins: Wins = serde_json::from_str(&json).unwrap();
+
+        assert!(json.ends_with(r#""player_count":2,"labels":["Negreanu","Hansen"]}"#));
+        assert_eq!(tally, back);
+        assert_eq!(tally.wins, wins);
+    }
+
+    #[test]
+    fn tally__from_wins_record() {
+        let json = serde_json::to_string(&the_hand_as_wins()).unwrap();
+
+        let tally: Tally = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(Tally::from(the_hand_as_wins()), tally);
+    }
+
+    #[test]
+    fn win_results__out_of_order() {
+        let json = r#"{"version":1,"case_count":1,"player_count":1,"players":[
+            {"index":1,"wins":1,"ties":0,"win_percentage":100.0,"tie_percentage":0.0,"equity_percentage":100.0}
+        ]}"#;
+
+        let error = serde_json::from_str::<WinResults>(json).unwrap_err();
+
+        assert!(error.to_string().contains("out of order"));
+    }
+
+    #[test]
+    fn win_results__player_count_mismatch() {
+        let json = r#"{"version":2,"case_count":1,"player_count":2,"players":[
+            {"index":0,"wins":1,"ties":0,"win_percentage":100.0,"tie_percentage":0.0,"equity_percentage":100.0}
+        ]}"#;
+
+        let error = serde_json::from_str::<WinResults>(json).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("1 player records for a player count of 2"));
+    }
+}
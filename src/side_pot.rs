@@ -0,0 +1,539 @@
+//! Side pots, for all-ins with unequal stacks.
+//!
+//! When a short stack goes all in against two bigger ones, there's more than one pot on the
+//! table. The short stack can only win the main pot, which everybody is in. The side pot is
+//! only between the two players who covered the extra chips. So a single winner flag per
+//! outcome isn't enough: the short stack can take the main pot while one of the others takes
+//! the side pot with the second best hand.
+//!
+//! [`SidePots`] keeps a separate [`Wins`] for every pot, and each outcome records who won each
+//! of them. [`SidePots::results()`] turns that into a [`SidePotResults`], with a `WinResults`
+//! per pot, and every player's expected chips across all of them.
+//!
+//! ```
+//! use wincounter::side_pot::SidePots;
+//! use wincounter::win::Win;
+//!
+//! // The first player is all in for 100, the other two for 300.
+//! let mut pots = SidePots::from_contributions(&[100.0, 300.0, 300.0]).unwrap();
+//!
+//! assert_eq!(300.0, pots.pots[0].amount);
+//! assert_eq!(400.0, pots.pots[1].amount);
+//!
+//! // The short stack scoops the main pot, and the second player takes the side pot.
+//! pots.add_x(&[Win::FIRST, Win::SECOND], 3).unwrap();
+//! // The second player takes both.
+//! pots.add(&[Win::SECOND, Win::SECOND]).unwrap();
+//!
+//! // The first player isn't in the side pot.
+//! assert!(pots.add(&[Win::FIRST, Win::FIRST]).is_err());
+//!
+//! let results = pots.results();
+//! let ev = results.expected_values().unwrap();
+//!
+//! assert_eq!((3, 0), results.pots[0].results.wins_and_ties(0));
+//! assert_eq!(225.0, ev[0].share);
+//! assert_eq!(475.0, ev[1].share);
+//! assert_eq!(0.0, ev[2].share);
+//! ```
+use crate::ev::{ExpectedValue, Stakes, StakesError};
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::wins::Wins;
+use crate::PlayerFlag;
+use std::fmt::{Display, Formatter};
+
+/// A pot and the players who can win it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pot<F: Flag = PlayerFlag> {
+    pub amount: f64,
+    pub eligible: F,
+}
+
+impl<F: Flag> Pot<F> {
+    #[must_use]
+    pub fn new(amount: f64, eligible: F) -> Self {
+        Self { amount, eligible }
+    }
+}
+
+/// Something wrong with an outcome, or with the pots.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SidePotError {
+    /// More players than the flag can hold.
+    TooManyPlayers { capacity: usize },
+    /// The outcome didn't have one winner set for every pot.
+    PotCount { pots: usize, winners: usize },
+    /// Players won a pot they weren't in. `stray` is their bits, widened to `u128`.
+    NotEligible { pot: usize, stray: u128 },
+}
+
+impl Display for SidePotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SidePotError::TooManyPlayers { capacity } => {
+                write!(f, "the flag only holds {capacity} players")
+            },
+            SidePotError::PotCount { pots, winners } => {
+                write!(f, "{winners} winner sets for {pots} pots")
+            },
+            SidePotError::NotEligible { pot, stray } => {
+                write!(f, "players {stray:#b} can't win pot {pot}")
+            },
+        }
+    }
+}
+
+impl std::error::Error for SidePotError {}
+
+/// The pots, and a `Wins` for each of them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SidePots<F: Flag = PlayerFlag> {
+    pub player_count: usize,
+    pub pots: Vec<Pot<F>>,
+    wins: Vec<Wins<F>>,
+}
+
+impl<F: Flag> SidePots<F> {
+    #[must_use]
+    pub fn new(player_count: usize, pots: Vec<Pot<F>>) -> Self {
+        let wins = vec![Wins::new(); pots.len()];
+        Self {
+            player_count,
+            pots,
+            wins,
+        }
+    }
+
+    /// Builds the main pot and side pots from how much each player put in, smallest first.
+    /// Every pot is everybody's chips up to the next all in, and only the players who put in
+    /// at least that much can win it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SidePotError::TooManyPlayers` if there are more contributions than the flag can
+    /// hold.
+    pub fn from_contributions(contributions: &[f64]) -> Result<Self, SidePotError> {
+        if contributions.len() > F::CAPACITY {
+            return Err(SidePotError::TooManyPlayers {
+                capacity: F::CAPACITY,
+            });
+        }
+        let mut levels: Vec<f64> = contributions.iter().copied().filter(|c| *c > 0.0).collect();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup();
+
+        let mut pots = Vec::new();
+        let mut previous = 0.0;
+        for level in levels {
+            let amount = contributions
+                .iter()
+                .map(|c| c.min(level) - c.min(previous))
+                .sum();
+            let eligible = contributions
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c >= level)
+                .filter_map(|(i, _)| F::from_index(i))
+                .fold(F::NONE, |eligible, player| eligible | player);
+            pots.push(Pot::new(amount, eligible));
+            previous = level;
+        }
+        Ok(Self::new(contributions.len(), pots))
+    }
+
+    /// Records one outcome, with the winners of each pot in the same order as the pots. An
+    /// empty flag is a pot nobody won.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SidePotError` if there isn't one winner set per pot, or if anybody won a pot
+    /// they weren't in. Nothing is recorded if so.
+    pub fn add(&mut self, winners: &[F]) -> Result<(), SidePotError> {
+        self.add_x(winners, 1)
+    }
+
+    /// `add()`, x number of times.
+    ///
+    /// # Errors
+    ///
+    /// The same as `add()`.
+    pub fn add_x(&mut self, winners: &[F], x: usize) -> Result<(), SidePotError> {
+        if winners.len() != self.pots.len() {
+            return Err(SidePotError::PotCount {
+                pots: self.pots.len(),
+                winners: winners.len(),
+            });
+        }
+        for (pot, (winner, eligible)) in winners
+            .iter()
+            .zip(self.pots.iter().map(|pot| pot.eligible))
+            .enumerate()
+        {
+            let stray = *winner & !eligible;
+            if !stray.is_none() {
+                return Err(SidePotError::NotEligible {
+                    pot,
+                    stray: stray.to_u128(),
+                });
+            }
+        }
+        for (wins, winner) in self.wins.iter_mut().zip(winners) {
+            if winner.is_none() {
+                wins.add_no_winner_x(x);
+            } else {
+                wins.add_x(*winner, x);
+            }
+        }
+        Ok(())
+    }
+
+    /// The `Wins` for a single pot.
+    #[must_use]
+    pub fn wins(&self, pot: usize) -> Option<&Wins<F>> {
+        self.wins.get(pot)
+    }
+
+    #[must_use]
+    pub fn results(&self) -> SidePotResults {
+        SidePotResults {
+            player_count: self.player_count,
+            pots: self
+                .pots
+                .iter()
+                .zip(&self.wins)
+                .map(|(pot, wins)| PotResults {
+                    amount: pot.amount,
+                    eligible: pot.eligible.indexes().collect(),
+                    results: WinResults::from_wins(wins, self.player_count),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The results for one pot.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PotResults {
+    pub amount: f64,
+    pub eligible: Vec<usize>,
+    pub results: WinResults,
+}
+
+impl PotResults {
+    /// The pot as `Stakes`, with the players in it having put in an even share. That's exactly
+    /// right for pots from `SidePots::from_contributions()`.
+    ///
+    /// The last player in gets whatever's left of the pot rather than the same share, so that
+    /// the contributions add up to exactly the pot. A pot of 100 split seven ways would otherwise
+    /// come to a hair over 100.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn stakes(&self) -> Stakes {
+        let mut contributions = vec![0.0; self.results.player_count];
+        let players: Vec<usize> = self
+            .eligible
+            .iter()
+            .copied()
+            .filter(|i| *i < contributions.len())
+            .collect();
+        if let Some((last, rest)) = players.split_last() {
+            let share = self.amount / players.len() as f64;
+            let mut given = 0.0;
+            for i in rest {
+                contributions[*i] = share;
+                given += share;
+            }
+            contributions[*last] = self.amount - given;
+        }
+        Stakes::new(self.amount, contributions)
+    }
+
+    /// Every player's expected result from this pot alone. A pot nobody won goes back to the
+    /// players in it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StakesError` if [`WinResults::expected_values()`] can't work them out, like for
+    /// a negative pot.
+    pub fn expected_values(&self) -> Result<Vec<ExpectedValue>, StakesError> {
+        self.results.expected_values(&self.stakes())
+    }
+}
+
+/// A `WinResults` for every pot, main pot first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SidePotResults {
+    pub player_count: usize,
+    pub pots: Vec<PotResults>,
+}
+
+impl SidePotResults {
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+        for pot in &mut self.pots {
+            pot.results.labels.clone_from(&labels);
+        }
+        self
+    }
+
+    /// Everything in every pot.
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.pots.iter().map(|pot| pot.amount).sum()
+    }
+
+    /// Every player's expected result from all of the pots put together. `equity` and
+    /// `break_even` are out of the `total()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `StakesError` from a pot's
+    /// [`expected_values()`](PotResults::expected_values).
+    pub fn expected_values(&self) -> Result<Vec<ExpectedValue>, StakesError> {
+        let total = self.total();
+        let mut evs = vec![ExpectedValue::default(); self.player_count];
+        for pot in &self.pots {
+            for (ev, pot_ev) in evs.iter_mut().zip(pot.expected_values()?) {
+                ev.share += pot_ev.share;
+                ev.contribution += pot_ev.contribution;
+            }
+        }
+        for ev in &mut evs {
+            ev.profit = ev.share - ev.contribution;
+            if total > 0.0 {
+                ev.equity = ev.share / total;
+                ev.break_even = ev.contribution / total;
+            }
+        }
+        Ok(evs)
+    }
+
+    /// The player's expected chips from all of the pots.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`expected_values()`](SidePotResults::expected_values).
+    pub fn expected_chips(&self, player_index: usize) -> Result<f64, StakesError> {
+        Ok(self
+            .expected_values()?
+            .get(player_index)
+            .map_or(0.0, |ev| ev.share))
+    }
+}
+
+/// Every pot, with a line for each player in it, then everybody's expected chips, or why they
+/// couldn't be worked out.
+///
+/// ```txt
+/// Main pot 300.00
+///   Player #1 75.0% (75.00%/0.00%) [3/0]
+///   ...
+/// Side pot 1 400.00
+///   ...
+/// Expected
+///   Player #1 +125.00 (225.00 back for 100.00, 32.14% of the pot, 14.29% to break even)
+///   ...
+/// ```
+impl Display for SidePotResults {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (n, pot) in self.pots.iter().enumerate() {
+            if n == 0 {
+                writeln!(f, "Main pot {:.2}", pot.amount)?;
+            } else {
+                writeln!(f, "Side pot {n} {:.2}", pot.amount)?;
+            }
+            for i in &pot.eligible {
+                writeln!(
+                    f,
+                    "  {} {}",
+                    pot.results.label(*i),
+                    pot.results.player_to_string(*i)
+                )?;
+            }
+        }
+        let labels = self.pots.first().map(|pot| &pot.results);
+        writeln!(f, "Expected")?;
+        match self.expected_values() {
+            Ok(evs) => {
+                for (i, ev) in evs.iter().enumerate() {
+                    let label = labels.map_or_else(|| format!("Player #{}", i + 1), |r| r.label(i));
+                    writeln!(f, "  {label} {ev}")?;
+                }
+            },
+            Err(e) => writeln!(f, "  {e}")?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::float_cmp, clippy::unwrap_used)]
+mod util__wincounter__side_pot__tests {
+    use super::*;
+    use crate::win::Win;
+
+    fn three_way() -> SidePots {
+        let mut pots = SidePots::from_contributions(&[100.0, 300.0, 300.0]).unwrap();
+        pots.add_x(&[Win::FIRST, Win::SECOND], 3).unwrap();
+        pots.add(&[Win::SECOND, Win::SECOND]).unwrap();
+        pots
+    }
+
+    #[test]
+    fn from_contributions() {
+        let pots =
+            SidePots::<PlayerFlag>::from_contributions(&[100.0, 300.0, 50.0, 300.0, 0.0]).unwrap();
+
+        assert_eq!(5, pots.player_count);
+        assert_eq!(
+            vec![
+                Pot::new(200.0, Win::FIRST | Win::SECOND | Win::THIRD | Win::FORTH),
+                Pot::new(150.0, Win::FIRST | Win::SECOND | Win::FORTH),
+                Pot::new(400.0, Win::SECOND | Win::FORTH),
+            ],
+            pots.pots
+        );
+    }
+
+    #[test]
+    fn from_contributions__too_many_players() {
+        assert_eq!(
+            Err(SidePotError::TooManyPlayers { capacity: 8 }),
+            SidePots::<u8>::from_contributions(&[1.0; 9])
+        );
+    }
+
+    #[test]
+    fn add() {
+        let pots = three_way();
+
+        assert_eq!(3, pots.wins(0).unwrap().count(Win::FIRST));
+        assert_eq!(4, pots.wins(1).unwrap().count(Win::SECOND));
+        assert_eq!(None, pots.wins(2));
+    }
+
+    #[test]
+    fn add__errors() {
+        let mut pots = three_way();
+
+        assert_eq!(
+            Err(SidePotError::PotCount {
+                pots: 2,
+                winners: 1
+            }),
+            pots.add(&[Win::FIRST])
+        );
+        assert_eq!(
+            "players 0b1 can't win pot 1",
+            pots.add(&[Win::SECOND, Win::FIRST | Win::THIRD])
+                .unwrap_err()
+                .to_string()
+        );
+        // Nothing was recorded for the main pot either.
+        assert_eq!(4, pots.wins(0).unwrap().len());
+    }
+
+    #[test]
+    fn add__no_winner() {
+        let mut pots = three_way();
+
+        pots.add(&[Win::SECOND | Win::THIRD, 0]).unwrap();
+
+        assert_eq!(1, pots.wins(1).unwrap().no_winner());
+    }
+
+    #[test]
+    fn results() {
+        let results = three_way().results();
+
+        assert_eq!(2, results.pots.len());
+        assert_eq!(vec![0, 1, 2], results.pots[0].eligible);
+        assert_eq!(vec![1, 2], results.pots[1].eligible);
+        assert_eq!((1, 0), results.pots[0].results.wins_and_ties(1));
+        assert_eq!(100.0, results.pots[1].results.wins_total_percentage(1));
+        assert_eq!(700.0, results.total());
+    }
+
+    #[test]
+    fn expected_values() {
+        let ev = three_way().results().expected_values().unwrap();
+
+        assert_eq!(225.0, ev[0].share);
+        assert_eq!(100.0, ev[0].contribution);
+        assert_eq!(125.0, ev[0].profit);
+        assert_eq!(475.0, ev[1].share);
+        assert_eq!(175.0, ev[1].profit);
+        assert_eq!(-300.0, ev[2].profit);
+        assert_eq!(300.0 / 700.0, ev[1].break_even);
+        assert_eq!(Ok(475.0), three_way().results().expected_chips(1));
+    }
+
+    #[test]
+    fn expected_values__seven_ways() {
+        let mut pots = SidePots::new(7, vec![Pot::new(100.0, 0b111_1111)]);
+        pots.add(&[Win::FIRST | Win::SECOND]).unwrap();
+
+        let results = pots.results();
+        let ev = results.expected_values().unwrap();
+
+        assert_eq!(100.0, results.pots[0].stakes().contributed());
+        assert_eq!(50.0, ev[0].share);
+        assert_eq!(50.0, ev[1].share);
+        assert_eq!(0.0, ev[6].share);
+        assert!(ev[6].contribution > 0.0);
+    }
+
+    #[test]
+    fn expected_values__errors() {
+        let mut pots = SidePots::new(2, vec![Pot::new(-10.0, Win::FIRST | Win::SECOND)]);
+        pots.add(&[Win::FIRST]).unwrap();
+        let results = pots.results();
+
+        assert_eq!(Err(StakesError::Negative), results.expected_values());
+        assert!(results.to_string().ends_with(
+            "Expected\n  the pot and contributions have to be finite and not negative\n"
+        ));
+    }
+
+    #[test]
+    fn expected_values__ties_and_refunds() {
+        let mut pots = SidePots::from_contributions(&[100.0, 200.0]).unwrap();
+        pots.add(&[Win::FIRST | Win::SECOND, Win::SECOND]).unwrap();
+        pots.add(&[0, 0]).unwrap();
+
+        let ev = pots.results().expected_values().unwrap();
+
+        // Half the main pot once, and their 100 back once.
+        assert_eq!(100.0, ev[0].share);
+        assert_eq!(200.0, ev[1].share);
+        assert_eq!(0.0, ev[1].profit);
+    }
+
+    #[test]
+    fn display() {
+        let results = three_way()
+            .results()
+            .with_labels(["Short", "Big", "Bigger"]);
+
+        assert_eq!(
+            "Main pot 300.00\n  \
+             Short 75.0% (75.00%/0.00%) [3/0]\n  \
+             Big 25.0% (25.00%/0.00%) [1/0]\n  \
+             Bigger 0.00%\n\
+             Side pot 1 400.00\n  \
+             Big 100.0% (100.00%/0.00%) [4/0]\n  \
+             Bigger 0.00%\n\
+             Expected\n  \
+             Short +125.00 (225.00 back for 100.00, 32.14% of the pot, 14.29% to break even)\n  \
+             Big +175.00 (475.00 back for 300.00, 67.86% of the pot, 42.86% to break even)\n  \
+             Bigger -300.00 (0.00 back for 300.00, 0.00% of the pot, 42.86% to break even)\n",
+            results.to_string()
+        );
+    }
+}
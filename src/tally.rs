@@ -0,0 +1,306 @@
+//! Saved tallies, and merging them back together.
+//!
+//! A batch job that's been split across machines ends up with a pile of partial `Wins`, each
+//! saved on its own. [`merge()`] sums them, after checking that they're all counting the same
+//! game.
+//!
+//! ```
+//! use wincounter::tally::{merge, Tally};
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let first = Tally::new(Wins::from(vec![Win::FIRST, Win::SECOND]), 2);
+//! let second = Tally::new(Wins::from(vec![Win::FIRST | Win::SECOND]), 2);
+//!
+//! let merged = merge(vec![first, second]).unwrap();
+//!
+//! assert_eq!(Some(2), merged.player_count);
+//! assert_eq!(3, merged.wins.len());
+//! ```
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::wins::Wins;
+use crate::PlayerFlag;
+use std::fmt::{Display, Formatter};
+
+/// A `Wins` along with the number of players it was counted for, if that's known, and their
+/// labels, if they have any. The CSV tally format doesn't record either, so a `Tally` loaded from
+/// one of those has `None` and no labels.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tally<F: Flag = PlayerFlag> {
+    pub wins: Wins<F>,
+    pub player_count: Option<usize>,
+    pub labels: Vec<String>,
+}
+
+impl<F: Flag> Tally<F> {
+    #[must_use]
+    pub fn new(wins: Wins<F>, player_count: usize) -> Self {
+        Self {
+            wins,
+            player_count: Some(player_count),
+            labels: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// One more than the highest player that shows up in any pattern.
+    #[must_use]
+    pub fn highest_player(&self) -> usize {
+        self.wins
+            .iter()
+            .filter_map(|(count, _)| count.indexes().last())
+            .max()
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Uses the saved player count, or the highest player seen if there isn't one, and the
+    /// saved labels.
+    #[must_use]
+    pub fn results(&self) -> WinResults {
+        self.wins
+            .results(self.player_count.unwrap_or_else(|| self.highest_player()))
+            .with_labels(self.labels.iter().cloned())
+    }
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Tally::from(Wins::default())
+    }
+}
+
+impl<F: Flag> From<Wins<F>> for Tally<F> {
+    fn from(wins: Wins<F>) -> Self {
+        Self {
+            wins,
+            player_count: None,
+            labels: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MergeError {
+    /// The tally at `index` was counted for a different number of players than the ones before
+    /// it.
+    PlayerCount {
+        index: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The tally at `index` has different labels than the ones before it.
+    Labels {
+        index: usize,
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+    /// The tally at `index` has a player in it past the agreed player count.
+    PlayerOutOfRange {
+        index: usize,
+        player_count: usize,
+        player: usize,
+    },
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::PlayerCount {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "tally {} has {found} players, expected {expected}",
+                index + 1
+            ),
+            MergeError::Labels {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "tally {} has players {}, expected {}",
+                index + 1,
+                found.join(", "),
+                expected.join(", ")
+            ),
+            MergeError::PlayerOutOfRange {
+                index,
+                player_count,
+                player,
+            } => write!(
+                f,
+                "tally {} has player #{player}, but there are only {player_count} players",
+                index + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Sums any number of tallies into one. Tallies that don't know their player count go along
+/// with whatever the others say, as long as none of their patterns have players past it. The
+/// same goes for tallies without labels.
+///
+/// # Errors
+///
+/// Returns `MergeError` if two tallies disagree on the number of players or their labels, or a
+/// tally has a player in it that the agreed player count doesn't allow for.
+pub fn merge<F, I>(tallies: I) -> Result<Tally<F>, MergeError>
+where
+    F: Flag,
+    I: IntoIterator<Item = Tally<F>>,
+{
+    let mut merged = Tally::from(Wins::new());
+    let mut highest = Vec::new();
+    for (index, tally) in tallies.into_iter().enumerate() {
+        if let Some(found) = tally.player_count {
+            match merged.player_count {
+                Some(expected) if expected != found => {
+                    return Err(MergeError::PlayerCount {
+                        index,
+                        expected,
+                        found,
+                    });
+                },
+                _ => merged.player_count = Some(found),
+            }
+        }
+        if !tally.labels.is_empty() {
+            if merged.labels.is_empty() {
+                merged.labels.clone_from(&tally.labels);
+            } else if merged.labels != tally.labels {
+                return Err(MergeError::Labels {
+                    index,
+                    expected: merged.labels,
+                    found: tally.labels,
+                });
+            }
+        }
+        highest.push(tally.highest_player());
+        merged.wins.extend(&tally.wins);
+    }
+    if let Some(player_count) = merged.player_count {
+        if let Some((index, player)) = highest
+            .into_iter()
+            .enumerate()
+            .find(|(_, player)| *player > player_count)
+        {
+            return Err(MergeError::PlayerOutOfRange {
+                index,
+                player_count,
+                player,
+            });
+        }
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__tally__tests {
+    use super::*;
+    use crate::win::Win;
+
+    #[test]
+    fn highest_player() {
+        assert_eq!(0, Tally::<u8>::from(Wins::new()).highest_player());
+        assert_eq!(
+            3,
+            Tally::from(Wins::from(vec![Win::FIRST, Win::SECOND | Win::THIRD])).highest_player()
+        );
+    }
+
+    #[test]
+    fn results() {
+        let wins = Wins::from(vec![Win::FIRST, Win::SECOND]);
+
+        assert_eq!(2, Tally::from(wins.clone()).results().player_count);
+        assert_eq!(4, Tally::new(wins, 4).results().player_count);
+    }
+
+    #[test]
+    fn merge() {
+        let merged = super::merge(vec![
+            Tally::new(Wins::from(vec![Win::FIRST]), 3),
+            Tally::from(Wins::from(vec![Win::FIRST, Win::THIRD])),
+            Tally::new(Wins::from(vec![Win::SECOND]), 3),
+        ])
+        .unwrap();
+
+        assert_eq!(Some(3), merged.player_count);
+        assert_eq!(2, merged.wins.count(Win::FIRST));
+        assert_eq!(4, merged.wins.len());
+    }
+
+    #[test]
+    fn merge__empty() {
+        assert_eq!(Tally::default(), super::merge(vec![]).unwrap());
+    }
+
+    #[test]
+    fn merge__player_count() {
+        let error = super::merge(vec![
+            Tally::new(Wins::from(vec![Win::FIRST]), 2),
+            Tally::new(Wins::from(vec![Win::FIRST]), 3),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            MergeError::PlayerCount {
+                index: 1,
+                expected: 2,
+                found: 3
+            },
+            error
+        );
+        assert_eq!("tally 2 has 3 players, expected 2", error.to_string());
+    }
+
+    #[test]
+    fn merge__labels() {
+        let merged = super::merge(vec![
+            Tally::from(Wins::from(vec![Win::FIRST])),
+            Tally::new(Wins::from(vec![Win::FIRST]), 2).with_labels(["Negreanu", "Hansen"]),
+        ])
+        .unwrap();
+        let error = super::merge(vec![
+            merged.clone(),
+            Tally::new(Wins::from(vec![Win::FIRST]), 2).with_labels(["Hansen", "Negreanu"]),
+        ])
+        .unwrap_err();
+
+        assert_eq!(vec!["Negreanu", "Hansen"], merged.labels);
+        assert_eq!("Negreanu", merged.results().label(0));
+        assert_eq!(
+            "tally 2 has players Hansen, Negreanu, expected Negreanu, Hansen",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn merge__player_out_of_range() {
+        let error = super::merge(vec![
+            Tally::from(Wins::from(vec![Win::THIRD])),
+            Tally::new(Wins::from(vec![Win::FIRST]), 2),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            "tally 1 has player #3, but there are only 2 players",
+            error.to_string()
+        );
+    }
+}
@@ -82,6 +82,13 @@ impl Util {
         s.replace('+', " ")
     }
 
+    /// For `#[serde(skip_serializing_if = "Util::is_zero")]`, which has to take a reference.
+    #[must_use]
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn is_zero(n: &usize) -> bool {
+        *n == 0
+    }
+
     #[must_use]
     pub fn str_remove_spaces(s: &str) -> String {
         s.replace(' ', "")
@@ -113,7 +120,12 @@ impl Util {
 }
 
 #[cfg(test)]
-#[allow(non_snake_case)]
+#[allow(
+    non_snake_case,
+    clippy::float_cmp,
+    clippy::uninlined_format_args,
+    clippy::unwrap_used
+)]
 mod util__tests {
     use super::*;
 
@@ -0,0 +1,259 @@
+//! Checking that a `Wins` actually fits the game it's being reported for.
+//!
+//! [`WinResults::from_wins()`] has always skipped over bits at or past the player count, and an
+//! empty flag doesn't credit anybody, so a stray `Win::FIFTH` in a heads up tally just makes the
+//! percentages stop adding up to 100%, with nothing to say why. [`Wins::validate()`] goes looking
+//! for those outcomes, and [`WinResults::try_from_wins()`] won't build results until it's happy.
+//!
+//! ```
+//! use wincounter::results::WinResults;
+//! use wincounter::validation::Problem;
+//! use wincounter::win::Win;
+//! use wincounter::wins::Wins;
+//!
+//! let wins = Wins::from(vec![Win::FIRST, Win::SECOND, Win::FIFTH, 0]);
+//!
+//! let error = WinResults::try_from_wins(&wins, 2).unwrap_err();
+//!
+//! assert_eq!(
+//!     vec![
+//!         Problem::EmptyFlag { count: 1 },
+//!         Problem::StrayBits { pattern: 0b1_0000, stray: 0b1_0000, count: 1 },
+//!         Problem::Total { case_count: 4, credited: 2 },
+//!     ],
+//!     error.problems
+//! );
+//! assert!(wins.validate(5).is_err());
+//! assert!(Wins::from(vec![Win::FIRST, Win::FIFTH]).validate(5).is_ok());
+//!
+//! let mut wins = Wins::from(vec![Win::FIRST]);
+//! wins.add_no_winner();
+//! assert!(wins.validate(1).is_ok());
+//! ```
+use crate::flag::Flag;
+use crate::results::WinResults;
+use crate::wins::Wins;
+use std::fmt::{Display, Formatter};
+
+/// One thing wrong with a `Wins` for a given player count.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    /// The player count is more than the flag can hold.
+    TooManyPlayers { capacity: usize },
+    /// `count` outcomes recorded as a `0` flag. If nobody winning is a real outcome in your game,
+    /// record it with [`Wins::add_no_winner()`] instead, which `validate()` is fine with.
+    EmptyFlag { count: usize },
+    /// `count` outcomes of `pattern` have players at or past the player count. `stray` is just
+    /// those players' bits. Patterns are widened to `u128` so the error doesn't need the flag
+    /// type.
+    StrayBits {
+        pattern: u128,
+        stray: u128,
+        count: usize,
+    },
+    /// Only `credited` of the `case_count` outcomes have a winner in range, so the wins won't
+    /// add up to the total.
+    Total { case_count: usize, credited: usize },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::TooManyPlayers { capacity } => {
+                write!(f, "the flag only holds {capacity} players")
+            },
+            Problem::EmptyFlag { count } => write!(f, "{count} outcomes with an empty flag"),
+            Problem::StrayBits {
+                pattern,
+                stray,
+                count,
+            } => write!(
+                f,
+                "{count} outcomes of {pattern:#b} have players out of range ({stray:#b})"
+            ),
+            Problem::Total {
+                case_count,
+                credited,
+            } => write!(
+                f,
+                "only {credited} of {case_count} outcomes have a winner in range"
+            ),
+        }
+    }
+}
+
+/// Everything [`Wins::validate()`] found wrong, in pattern order, with the totals last.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationError {
+    pub player_count: usize,
+    pub problems: Vec<Problem>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let problems: Vec<String> = self.problems.iter().map(ToString::to_string).collect();
+        write!(
+            f,
+            "invalid for {} players: {}",
+            self.player_count,
+            problems.join("; ")
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<F: Flag> Wins<F> {
+    /// Checks every pattern against the player count.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError` listing any empty outcomes, patterns with players at or past
+    /// `player_count`, and, if any outcomes don't credit a player in range, the shortfall.
+    pub fn validate(&self, player_count: usize) -> Result<(), ValidationError> {
+        let mut problems = Vec::new();
+        if player_count > F::CAPACITY {
+            problems.push(Problem::TooManyPlayers {
+                capacity: F::CAPACITY,
+            });
+        }
+        let mut case_count = 0;
+        let mut credited = 0;
+        for (count, x) in self.iter() {
+            case_count += x;
+            if count.is_none() {
+                problems.push(Problem::EmptyFlag { count: x });
+                continue;
+            }
+            let stray = count
+                .indexes()
+                .filter(|i| *i >= player_count)
+                .fold(0_u128, |stray, i| stray | 1 << i);
+            if stray != 0 {
+                problems.push(Problem::StrayBits {
+                    pattern: count.to_u128(),
+                    stray,
+                    count: x,
+                });
+            }
+            if count.indexes().any(|i| i < player_count) {
+                credited += x;
+            }
+        }
+        if credited != case_count {
+            problems.push(Problem::Total {
+                case_count,
+                credited,
+            });
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError {
+                player_count,
+                problems,
+            })
+        }
+    }
+}
+
+impl WinResults {
+    /// `from_wins()`, but only if `Wins::validate()` passes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `ValidationError` from `Wins::validate()`.
+    pub fn try_from_wins<F: Flag>(
+        wins: &Wins<F>,
+        player_count: usize,
+    ) -> Result<WinResults, ValidationError> {
+        wins.validate(player_count)?;
+        Ok(WinResults::from_wins(wins, player_count))
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case, clippy::unwrap_used)]
+mod util__wincounter__validation__tests {
+    use super::*;
+    use crate::win::Win;
+
+    #[test]
+    fn validate() {
+        let wins = Wins::from(vec![Win::FIRST, Win::SECOND, Win::FIRST | Win::SECOND]);
+
+        assert!(wins.validate(2).is_ok());
+        assert!(wins.validate(16).is_ok());
+        assert!(Wins::default().validate(0).is_ok());
+    }
+
+    #[test]
+    fn validate__stray_bits_in_a_tie() {
+        let mut wins = Wins::default();
+        wins.add_x(Win::FIRST | Win::THIRD, 3);
+
+        let error = wins.validate(2).unwrap_err();
+
+        // The first player still gets credit, so the total is fine.
+        assert_eq!(
+            vec![Problem::StrayBits {
+                pattern: 0b101,
+                stray: 0b100,
+                count: 3
+            }],
+            error.problems
+        );
+        assert_eq!(
+            "invalid for 2 players: 3 outcomes of 0b101 have players out of range (0b100)",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn validate__no_winner() {
+        let mut wins = Wins::from(vec![Win::FIRST]);
+        wins.add_x(0, 2);
+
+        assert_eq!(
+            "invalid for 1 players: 2 outcomes with an empty flag; only 1 of 3 outcomes have a winner \
+             in range",
+            wins.validate(1).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn validate__too_many_players() {
+        let wins: Wins<u8> = Wins::from(vec![0b1]);
+
+        assert_eq!(
+            vec![Problem::TooManyPlayers { capacity: 8 }],
+            wins.validate(9).unwrap_err().problems
+        );
+    }
+
+    #[test]
+    fn validate__wide_flags() {
+        let wins: Wins<u128> = Wins::from(vec![1 << 127]);
+
+        assert!(wins.validate(128).is_ok());
+        assert_eq!(
+            Problem::StrayBits {
+                pattern: 1 << 127,
+                stray: 1 << 127,
+                count: 1
+            },
+            wins.validate(100).unwrap_err().problems[0]
+        );
+    }
+
+    #[test]
+    fn try_from_wins() {
+        let wins = Wins::from(vec![Win::FIRST, Win::SECOND]);
+
+        assert_eq!(
+            WinResults::from_wins(&wins, 2),
+            WinResults::try_from_wins(&wins, 2).unwrap()
+        );
+        assert!(WinResults::try_from_wins(&wins, 1).is_err());
+    }
+}
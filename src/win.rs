@@ -98,6 +98,10 @@ impl Win {
     /// `wincounter::Count`. Not doing things right in order to save you some time will always
     /// end up taking more time in the long run. Count on it.
     ///
+    /// UPDATE: Indexes past 15 still return an empty flag here. For a checked version that
+    /// returns `None` instead, and that works with flags wider than `u16`, use
+    /// [`Flag::from_index()`](crate::flag::Flag::from_index), or
+    /// [`Player::new()`](crate::player::Player::new) for one that says what went wrong.
     #[must_use]
     pub fn from_index(i: usize) -> PlayerFlag {
         match i {
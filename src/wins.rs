@@ -1,73 +1,224 @@
+use crate::flag::Flag;
 use crate::heads_up::HeadsUp;
+use crate::player::{Player, PlayerSet};
+use crate::results::WinResults;
 use crate::util::Util;
-use crate::win::Win;
 use crate::{PlayerFlag, WinResult};
+use std::collections::BTreeMap;
 
 /// I've moved wincounter into the library so that I can make updates to the library
 /// as a part of this work. The plan is to later on move the updated module back to
 /// its own crate.
 ///
 /// When I originally wrote the crate I was just focused on heads up play.
-#[derive(Clone, Debug, Default)]
-pub struct Wins(Vec<PlayerFlag>);
+///
+/// # Histogram
+///
+/// Originally this was a `Vec<PlayerFlag>` with one entry for every single outcome. Counting
+/// the 1.7 million boards of The Hand meant pushing the same two or three flags onto a vector
+/// over and over again. Now `Wins` keeps a count for each distinct `PlayerFlag`, so memory is
+/// bounded by the number of distinct tie patterns, not the number of outcomes, and `add_x` is
+/// a single addition.
+///
+/// # Flag width
+///
+/// `Wins` defaults to `PlayerFlag` (`u16`), but any [`Flag`] width can be used for games with
+/// more than 16 players. `Default` is only implemented for the `u16` version so that
+/// `Wins::default()` keeps working without type annotations. Use `Wins::new()` for the others.
+///
+/// ```
+/// use wincounter::flag::Flag;
+/// use wincounter::wins::Wins;
+///
+/// let mut wins: Wins<u64> = Wins::new();
+/// let fortieth = u64::from_index(39).unwrap();
+/// wins.add_x(fortieth, 3);
+/// wins.add(fortieth | u64::from_index(0).unwrap());
+///
+/// let results = wins.results(40);
+/// assert_eq!((3, 1), results.wins_and_ties(39));
+/// assert_eq!((0, 1), results.wins_and_ties(0));
+/// ```
+///
+/// # No winner
+///
+/// Some games end with nobody winning at all: everybody busts, the hand is void, or it's a draw
+/// with no pot to share. Those are counted on their own with [`Wins::add_no_winner()`], apart
+/// from the flag patterns, so they can't be mixed up with a `0` that came out of a bad index.
+/// They're part of [`Wins::len()`], so every player's percentages are out of all the outcomes,
+/// not just the ones somebody won.
+///
+/// ```
+/// use wincounter::win::Win;
+/// use wincounter::wins::Wins;
+///
+/// let mut wins = Wins::from(vec![Win::FIRST, Win::SECOND, Win::FIRST]);
+/// wins.add_no_winner();
+///
+/// assert_eq!(4, wins.len());
+/// assert_eq!(1, wins.no_winner());
+/// assert_eq!(0, wins.count(0));
+/// assert_eq!(50.0, wins.results(2).wins_and_ties_percentages(0).0);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wins<F: Flag = PlayerFlag> {
+    counts: BTreeMap<F, usize>,
+    no_winner: usize,
+}
+
+impl<F: Flag> Wins<F> {
+    #[must_use]
+    pub fn new() -> Self {
+        Wins {
+            counts: BTreeMap::new(),
+            no_winner: 0,
+        }
+    }
 
-impl Wins {
-    pub fn add(&mut self, count: PlayerFlag) {
-        self.0.push(count);
+    pub fn add(&mut self, count: F) {
+        self.add_x(count, 1);
     }
 
-    /// Adds a count x number of times. Primarily used for testing.
-    pub fn add_x(&mut self, count: PlayerFlag, x: usize) {
-        for _ in 0..x {
-            self.0.push(count);
+    /// Adds a count x number of times.
+    pub fn add_x(&mut self, count: F, x: usize) {
+        if x > 0 {
+            *self.counts.entry(count).or_default() += x;
         }
     }
 
+    /// Adds an outcome where nobody won.
+    pub fn add_no_winner(&mut self) {
+        self.add_no_winner_x(1);
+    }
+
+    pub fn add_no_winner_x(&mut self, x: usize) {
+        self.no_winner += x;
+    }
+
+    /// The number of outcomes where nobody won.
+    #[must_use]
+    pub fn no_winner(&self) -> usize {
+        self.no_winner
+    }
+
+    /// Adds an outcome won by everyone in the set, which is a tie if there's more than one.
+    pub fn add_players(&mut self, players: PlayerSet<F>) {
+        self.add(players.flag());
+    }
+
     pub fn add_win_first(&mut self) {
-        self.0.push(Win::FIRST);
+        self.add_win_for(0);
     }
 
     pub fn add_win_second(&mut self) {
-        self.0.push(Win::SECOND);
+        self.add_win_for(1);
     }
 
     pub fn add_win_third(&mut self) {
-        self.0.push(Win::THIRD);
+        self.add_win_for(2);
+    }
+
+    /// Every supported `Flag` width has room for at least eight players, so the `add_win_*`
+    /// helpers can't miss.
+    fn add_win_for(&mut self, index: usize) {
+        if let Some(count) = F::from_index(index) {
+            self.add(count);
+        }
+    }
+
+    pub fn extend(&mut self, other: &Wins<F>) {
+        for (count, x) in other.iter() {
+            self.add_x(count, x);
+        }
+        self.add_no_winner_x(other.no_winner);
     }
 
-    pub fn extend(&mut self, other: &Wins) {
-        self.0.extend(other.get());
+    /// Returns the number of times that the exact `PlayerFlag` pattern was recorded.
+    ///
+    /// ```
+    /// use wincounter::wins::Wins;
+    /// use wincounter::win::Win;
+    ///
+    /// let wins = Wins::from(vec![Win::FIRST, Win::FIRST | Win::SECOND, Win::FIRST]);
+    ///
+    /// assert_eq!(2, wins.count(Win::FIRST));
+    /// assert_eq!(1, wins.count(Win::FIRST | Win::SECOND));
+    /// assert_eq!(0, wins.count(Win::SECOND));
+    /// ```
+    #[must_use]
+    pub fn count(&self, count: F) -> usize {
+        self.counts.get(&count).copied().unwrap_or_default()
     }
 
+    /// Returns the histogram of distinct `PlayerFlag` patterns and how many times each was
+    /// recorded. No-winner outcomes aren't in it.
     #[must_use]
-    pub fn get(&self) -> &Vec<PlayerFlag> {
-        &self.0
+    pub fn get(&self) -> &BTreeMap<F, usize> {
+        &self.counts
     }
 
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.counts.is_empty() && self.no_winner == 0
+    }
+
+    /// Iterates over each distinct `PlayerFlag` pattern, in ascending order, along with the
+    /// number of times it was recorded. No-winner outcomes aren't included, see
+    /// [`Wins::no_winner()`].
+    pub fn iter(&self) -> impl Iterator<Item = (F, usize)> + '_ {
+        self.counts.iter().map(|(count, x)| (*count, *x))
     }
 
+    /// The total number of outcomes recorded, including the ones nobody won.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.counts.values().sum::<usize>() + self.no_winner
+    }
+
+    /// Returns the number of distinct `PlayerFlag` patterns that have been recorded.
+    #[must_use]
+    pub fn patterns(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Aggregates every player's wins and ties, along with the total number of cases, in a
+    /// single pass. Shorthand for `WinResults::from_wins()`.
+    ///
+    /// ```
+    /// use wincounter::wins::Wins;
+    /// use wincounter::win::Win;
+    ///
+    /// let mut wins = Wins::default();
+    /// wins.add_x(Win::FIRST, 80);
+    /// wins.add_x(Win::SECOND, 15);
+    /// wins.add_x(Win::FIRST | Win::SECOND, 5);
+    ///
+    /// let results = wins.results(2);
+    ///
+    /// assert_eq!(100, results.case_count);
+    /// assert_eq!((80, 5), results.wins_and_ties(0));
+    /// assert_eq!((15, 5), results.wins_and_ties(1));
+    /// ```
+    #[must_use]
+    pub fn results(&self, player_count: usize) -> WinResults {
+        WinResults::from_wins(self, player_count)
     }
 
     /// Returns the cumulative wins for a specific `PlayerFlag`, and their number
     /// of ties.
     #[must_use]
-    pub fn wins_for(&self, result: PlayerFlag) -> (usize, usize) {
-        let wins: Vec<PlayerFlag> = self
-            .0
-            .clone()
-            .into_iter()
-            .filter(|r| r.win_for(result))
-            .collect();
-        (
-            wins.len(),
-            wins.into_iter().filter(WinResult::is_tie).count(),
-        )
+    pub fn wins_for(&self, result: F) -> (usize, usize) {
+        self.iter()
+            .filter(|(r, _)| r.win_for(result))
+            .fold((0, 0), |(wins, ties), (r, x)| {
+                (wins + x, if r.is_tie() { ties + x } else { ties })
+            })
+    }
+
+    /// `wins_for()` for a single typed player.
+    #[must_use]
+    pub fn wins_for_player(&self, player: Player<F>) -> (usize, usize) {
+        self.wins_for(player.flag())
     }
 
     /// Pass in a zero based player index and the function will return the
@@ -76,7 +227,7 @@ impl Wins {
     #[must_use]
     pub fn percentage_for_player(&self, index: usize) -> (f32, f32) {
         let total = self.len();
-        let (wins, ties) = self.wins_for(Win::from_index(index));
+        let (wins, ties) = F::from_index(index).map_or((0, 0), |count| self.wins_for(count));
         let pure_wins = wins - ties;
 
         (
@@ -100,22 +251,71 @@ impl Wins {
     ///
     #[must_use]
     pub fn results_heads_up(&self) -> HeadsUp {
-        let (first, ties) = self.wins_for(Win::FIRST);
-        let (second, _) = self.wins_for(Win::SECOND);
-        HeadsUp::new(first - ties, second - ties, ties)
+        HeadsUp::from(&self.results(2))
+    }
+}
+
+impl Default for Wins {
+    fn default() -> Self {
+        Wins::new()
     }
 }
 
-impl From<Vec<PlayerFlag>> for Wins {
-    fn from(counts: Vec<PlayerFlag>) -> Self {
-        Wins(counts)
+impl<F: Flag> From<Vec<F>> for Wins<F> {
+    fn from(counts: Vec<F>) -> Self {
+        counts.into_iter().collect()
+    }
+}
+
+impl<F: Flag> FromIterator<F> for Wins<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut wins = Wins::new();
+        for count in iter {
+            wins.add(count);
+        }
+        wins
     }
 }
 
 #[cfg(test)]
-#[allow(non_snake_case)]
+#[allow(
+    non_snake_case,
+    clippy::float_cmp,
+    clippy::unreadable_literal,
+    clippy::unwrap_used
+)]
 mod util__wincounter__wins__tests {
     use super::*;
+    use crate::win::Win;
+
+    #[test]
+    fn add_players() {
+        let third = Player::new(2).unwrap();
+        let mut wins = Wins::default();
+
+        wins.add_players(PlayerSet::from(third));
+        wins.add_players(PlayerSet::from_indexes([0, 2]).unwrap());
+
+        assert_eq!(1, wins.count(Win::FIRST | Win::THIRD));
+        assert_eq!((2, 1), wins.wins_for_player(third));
+    }
+
+    #[test]
+    fn add_no_winner() {
+        let mut wins = Wins::from(vec![Win::FIRST]);
+        let mut other = Wins::default();
+        other.add_no_winner_x(3);
+
+        wins.add_no_winner();
+        wins.extend(&other);
+
+        assert_eq!(4, wins.no_winner());
+        assert_eq!(5, wins.len());
+        assert_eq!(1, wins.patterns());
+        assert_eq!(0, wins.count(0));
+        assert!(!other.is_empty());
+        assert_ne!(Wins::default(), Wins::from(vec![0]));
+    }
 
     #[test]
     fn extend() {
@@ -130,13 +330,28 @@ mod util__wincounter__wins__tests {
         assert_eq!(more_wins.len() + even_more_wins.len(), wins.len());
     }
 
+    #[test]
+    fn extend__merges_patterns() {
+        let mut wins = Wins::from(vec![Win::FIRST, Win::FIRST | Win::SECOND]);
+
+        wins.extend(&Wins::from(vec![Win::FIRST, Win::SECOND]));
+
+        assert_eq!(2, wins.count(Win::FIRST));
+        assert_eq!(1, wins.count(Win::SECOND));
+        assert_eq!(1, wins.count(Win::FIRST | Win::SECOND));
+        assert_eq!(3, wins.patterns());
+    }
+
     #[test]
     fn get() {
         let v = vec![Win::FIRST, Win::FIRST, Win::SECOND, Win::FIRST];
 
-        let wins = Wins::from(v.clone());
+        let wins = Wins::from(v);
 
-        assert_eq!(&v, wins.get())
+        assert_eq!(
+            &BTreeMap::from([(Win::FIRST, 3), (Win::SECOND, 1)]),
+            wins.get()
+        );
     }
 
     #[test]
@@ -150,7 +365,7 @@ mod util__wincounter__wins__tests {
         counter.add(Win::FIRST | Win::SECOND);
         counter.add(Win::FIFTH);
 
-        assert_eq!(6, counter.len())
+        assert_eq!(6, counter.len());
     }
 
     #[test]
@@ -166,6 +381,17 @@ mod util__wincounter__wins__tests {
         assert_eq!((1_365_284 + 32116, 32116), wins.wins_for(Win::FIRST));
         assert_eq!((314_904 + 32116, 32116), wins.wins_for(Win::SECOND));
         assert_eq!((32116, 32116), wins.wins_for(Win::FIRST | Win::SECOND));
+        assert_eq!(3, wins.patterns());
+    }
+
+    #[test]
+    fn add_x__zero() {
+        let mut wins = Wins::default();
+
+        wins.add_x(Win::FIRST, 0);
+
+        assert!(wins.is_empty());
+        assert_eq!(0, wins.patterns());
     }
 
     #[test]
@@ -178,6 +404,16 @@ mod util__wincounter__wins__tests {
         assert!(Wins::default().is_empty());
     }
 
+    #[test]
+    fn iter() {
+        let wins = Wins::from(vec![Win::SECOND, Win::FIRST, Win::SECOND]);
+
+        assert_eq!(
+            vec![(Win::FIRST, 1), (Win::SECOND, 2)],
+            wins.iter().collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn len() {
         let mut counter = Wins::default();
@@ -224,6 +460,31 @@ mod util__wincounter__wins__tests {
         assert_eq!(1.8756015_f32, tie_pct2);
     }
 
+    #[test]
+    fn percentage_for_player__out_of_range() {
+        let wins = Wins::from(vec![Win::FIRST]);
+
+        assert_eq!((0.0, 0.0), wins.percentage_for_player(16));
+    }
+
+    #[test]
+    fn wider_flags() {
+        let mut wins: Wins<u128> = Wins::new();
+        let last = u128::from_index(127).unwrap();
+        let first = u128::from_index(0).unwrap();
+        wins.add_x(last, 2);
+        wins.add(last | first);
+        wins.add_win_first();
+
+        assert_eq!((3, 1), wins.wins_for(last));
+        assert_eq!((2, 1), wins.wins_for(first));
+        assert_eq!(4, wins.len());
+
+        let results = wins.results(128);
+        assert_eq!((2, 1), results.wins_and_ties(127));
+        assert_eq!((1, 1), results.wins_and_ties(0));
+    }
+
     #[test]
     fn results_heads_up() {
         let mut counter = Wins::default();